@@ -0,0 +1,74 @@
+use bevy::{
+    prelude::{
+        in_state, BuildChildren, Commands, Component, DespawnRecursiveExt, Entity, Handle,
+        IntoSystemConfigs, Plugin, Query, Transform, Update, Vec2, With,
+    },
+    sprite::{SpriteSheetBundle, TextureAtlas, TextureAtlasSprite},
+};
+
+use crate::{
+    level::{Ability, AbilityPickupDefinition},
+    player::{Abilities, Player},
+    GameState,
+};
+
+/// Not shared with any other feature, so pickups can't be mistaken for something else, like
+/// the exit sign.
+const PICKUP_SPRITE_INDEX: usize = 64;
+
+pub struct AbilityPlugin;
+
+impl Plugin for AbilityPlugin {
+    fn build(&self, app: &mut bevy::prelude::App) {
+        app.add_systems(
+            Update,
+            (collect_abilities,).run_if(in_state(GameState::Playing)),
+        );
+    }
+}
+
+/// Grants its ability to the player for the rest of the level when touched.
+#[derive(Component, Debug)]
+pub struct AbilityPickup {
+    pub ability: Ability,
+}
+
+pub fn setup(
+    commands: &mut Commands,
+    tile_atlas: &Handle<TextureAtlas>,
+    level: Entity,
+    pickup: &AbilityPickupDefinition,
+) {
+    let mut sprite = TextureAtlasSprite::new(PICKUP_SPRITE_INDEX);
+    sprite.custom_size = Some(Vec2::new(1.0, 1.0));
+
+    commands.entity(level).with_children(|children| {
+        children
+            .spawn(SpriteSheetBundle {
+                sprite,
+                texture_atlas: tile_atlas.clone(),
+                transform: Transform::from_translation(pickup.location.extend(1.0)),
+                ..SpriteSheetBundle::default()
+            })
+            .insert(AbilityPickup {
+                ability: pickup.ability,
+            });
+    });
+}
+
+fn collect_abilities(
+    mut commands: Commands,
+    pickups: Query<(Entity, &AbilityPickup, &Transform)>,
+    mut players: Query<(&Transform, &mut Abilities), With<Player>>,
+) {
+    for (player_transform, mut abilities) in players.iter_mut() {
+        for (pickup_entity, pickup, pickup_transform) in pickups.iter() {
+            if (player_transform.translation.x - pickup_transform.translation.x).abs() < 0.75
+                && (player_transform.translation.y - pickup_transform.translation.y).abs() < 1.0
+            {
+                commands.entity(pickup_entity).despawn_recursive();
+                abilities.0.insert(pickup.ability);
+            }
+        }
+    }
+}
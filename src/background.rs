@@ -0,0 +1,106 @@
+use bevy::{
+    prelude::{
+        AssetServer, BuildChildren, Commands, Component, Entity, Handle, Image, IntoSystemConfigs,
+        Plugin, PostUpdate, Query, SpatialBundle, Transform, Vec2, Vec3, With, Without,
+    },
+    sprite::{Sprite, SpriteBundle},
+    transform::TransformSystem,
+};
+use bevy_rapier2d::prelude::PhysicsSet;
+
+use crate::{level::BackgroundDefinition, player::Player};
+
+/// Width of the visible world, from `ScalingMode::FixedHorizontal(10.0)` scaled by 4.
+const VIEW_WIDTH: f32 = 40.0;
+const BACKGROUND_DEFAULT_Z: f32 = -10.0;
+
+pub struct BackgroundPlugin;
+
+impl Plugin for BackgroundPlugin {
+    fn build(&self, app: &mut bevy::prelude::App) {
+        // The camera follows the player, so scroll once the player has moved for this frame.
+        app.add_systems(
+            PostUpdate,
+            scroll_layers
+                .after(PhysicsSet::Writeback)
+                .before(TransformSystem::TransformPropagate),
+        );
+    }
+}
+
+#[derive(Component, Debug)]
+pub struct ParallaxLayer {
+    pub factor: f32,
+    pub width: f32,
+    pub repeat: bool,
+    pub offset: Vec2,
+}
+
+pub fn setup(
+    commands: &mut Commands,
+    server: &AssetServer,
+    level: Entity,
+    background: &BackgroundDefinition,
+) {
+    let texture: Handle<Image> = server.load(background.image.as_str());
+    let width = background.size.x;
+    // Enough copies on each side to cover the view wherever the layer has been snapped to.
+    let copies = if background.repeat {
+        ((VIEW_WIDTH + width) / (2.0 * width)).ceil() as i32
+    } else {
+        0
+    };
+
+    commands.entity(level).with_children(|children| {
+        children
+            .spawn(SpatialBundle::from_transform(Transform::from_translation(
+                background
+                    .offset
+                    .extend(background.z.unwrap_or(BACKGROUND_DEFAULT_Z)),
+            )))
+            .insert(ParallaxLayer {
+                factor: background.parallax,
+                width,
+                repeat: background.repeat,
+                offset: background.offset,
+            })
+            .with_children(|children| {
+                for copy in -copies..=copies {
+                    children.spawn(SpriteBundle {
+                        sprite: Sprite {
+                            custom_size: Some(background.size),
+                            ..Sprite::default()
+                        },
+                        texture: texture.clone(),
+                        transform: Transform::from_translation(Vec3::new(
+                            copy as f32 * width,
+                            0.0,
+                            0.0,
+                        )),
+                        ..SpriteBundle::default()
+                    });
+                }
+            });
+    });
+}
+
+fn scroll_layers(
+    players: Query<&Transform, With<Player>>,
+    mut layers: Query<(&ParallaxLayer, &mut Transform), Without<Player>>,
+) {
+    if players.is_empty() {
+        return;
+    }
+    let camera_position = players.single().translation.truncate();
+
+    for (layer, mut transform) in layers.iter_mut() {
+        let mut position = layer.offset + camera_position * layer.factor;
+        if layer.repeat {
+            // Jump a whole image width at a time to keep a copy centered under the camera.
+            let drift = camera_position.x * (1.0 - layer.factor) - layer.offset.x;
+            position.x += (drift / layer.width).round() * layer.width;
+        }
+        transform.translation.x = position.x;
+        transform.translation.y = position.y;
+    }
+}
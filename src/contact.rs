@@ -0,0 +1,120 @@
+use bevy::prelude::{Component, Entity, IntoSystemConfigs, Plugin, PostUpdate, Query, Res, Vec2};
+use bevy_rapier2d::prelude::{ContactPairView, PhysicsSet, RapierContext, Velocity};
+
+use crate::player::Direction;
+
+/// How far from vertical a contact normal may be for a body to count as standing on the other.
+const STANDING_NORMAL_MIN_Y: f32 = 0.7;
+/// How far from horizontal a contact normal may be for the other collider to count as a wall.
+const WALL_NORMAL_MIN_X: f32 = 0.7;
+
+pub struct ContactPlugin;
+
+impl Plugin for ContactPlugin {
+    fn build(&self, app: &mut bevy::prelude::App) {
+        // Contacts are only up to date once the physics step has run, so check right after it
+        // and let the next frame's systems read the result.
+        app.add_systems(
+            PostUpdate,
+            (update_grounded, update_wall_contact).after(PhysicsSet::Writeback),
+        );
+    }
+}
+
+/// Whether the body is standing on something, judged from the normals of its active contacts.
+#[derive(Component, Debug, Default)]
+pub struct Grounded(pub bool);
+
+/// Velocity of whatever the body stands on, so a body riding a moving platform can tell its own
+/// motion apart. Zero in the air and on static ground.
+#[derive(Component, Debug, Default)]
+pub struct GroundVelocity(pub Vec2);
+
+/// Which side of the body a wall is touching, if any.
+#[derive(Component, Debug, Default)]
+pub struct WallContact(pub Option<Direction>);
+
+/// Normals of the active contacts between `body` and the other collider of the pair, pointing
+/// away from the other collider towards `body`.
+fn normals_towards<'a>(
+    contact_pair: &'a ContactPairView<'a>,
+    body: Entity,
+) -> impl Iterator<Item = Vec2> + 'a {
+    // The manifold normal points from the first collider towards the second.
+    let sign = if contact_pair.collider2() == body {
+        1.0
+    } else if contact_pair.collider1() == body {
+        -1.0
+    } else {
+        0.0
+    };
+    let is_active = sign != 0.0 && contact_pair.has_any_active_contacts();
+    contact_pair
+        .manifolds()
+        .filter(move |_| is_active)
+        .map(move |manifold| manifold.normal() * sign)
+}
+
+/// The collider of the pair that isn't `body`.
+pub fn other_collider(contact_pair: &ContactPairView, body: Entity) -> Entity {
+    if contact_pair.collider1() == body {
+        contact_pair.collider2()
+    } else {
+        contact_pair.collider1()
+    }
+}
+
+/// True if `body` is one of the pair and rests on top of the other collider.
+pub fn is_standing(contact_pair: &ContactPairView, body: Entity) -> bool {
+    normals_towards(contact_pair, body).any(|normal| normal.y > STANDING_NORMAL_MIN_Y)
+}
+
+/// The side of `body` the other collider of the pair touches, if it is a wall.
+fn wall_side(contact_pair: &ContactPairView, body: Entity) -> Option<Direction> {
+    normals_towards(contact_pair, body).find_map(|normal| {
+        if normal.x > WALL_NORMAL_MIN_X {
+            Some(Direction::Left)
+        } else if normal.x < -WALL_NORMAL_MIN_X {
+            Some(Direction::Right)
+        } else {
+            None
+        }
+    })
+}
+
+fn update_grounded(
+    context: Res<RapierContext>,
+    velocities: Query<&Velocity>,
+    mut bodies: Query<(Entity, &mut Grounded, Option<&mut GroundVelocity>)>,
+) {
+    for (entity, mut grounded, ground_velocity) in bodies.iter_mut() {
+        let support = context
+            .contacts_with(entity)
+            .find(|contact_pair| is_standing(contact_pair, entity))
+            .map(|contact_pair| other_collider(&contact_pair, entity));
+        let is_grounded = support.is_some();
+        if grounded.0 != is_grounded {
+            grounded.0 = is_grounded;
+        }
+
+        if let Some(mut ground_velocity) = ground_velocity {
+            let velocity = support
+                .and_then(|support| velocities.get(support).ok())
+                .map_or(Vec2::ZERO, |velocity| velocity.linvel);
+            if ground_velocity.0 != velocity {
+                ground_velocity.0 = velocity;
+            }
+        }
+    }
+}
+
+fn update_wall_contact(context: Res<RapierContext>, mut bodies: Query<(Entity, &mut WallContact)>) {
+    for (entity, mut wall_contact) in bodies.iter_mut() {
+        let side = context
+            .contacts_with(entity)
+            .find_map(|contact_pair| wall_side(&contact_pair, entity));
+        if wall_contact.0 != side {
+            wall_contact.0 = side;
+        }
+    }
+}
@@ -0,0 +1,67 @@
+use std::time::Duration;
+
+use bevy::prelude::{in_state, Component, Entity, IntoSystemConfigs, Plugin, Query, Res, Update};
+use bevy_rapier2d::prelude::{RapierContext, Velocity};
+
+use crate::{animation::Animation, contact, GameState};
+
+const BELT_FRAMES: &[usize] = &[62, 69, 76];
+const BELT_FRAMES_REVERSED: &[usize] = &[76, 69, 62];
+const BELT_FRAME_DELAY: Duration = Duration::from_millis(100);
+
+pub struct ConveyorPlugin;
+
+impl Plugin for ConveyorPlugin {
+    fn build(&self, app: &mut bevy::prelude::App) {
+        app.add_systems(Update, (carry_riders,).run_if(in_state(GameState::Playing)));
+    }
+}
+
+#[derive(Component, Debug)]
+pub struct Conveyor {
+    pub speed: f32,
+}
+
+/// The part of a body's horizontal velocity that comes from the conveyor it stands on. Code
+/// that steers the body works on the rest.
+#[derive(Component, Debug, Default)]
+pub struct Carried(pub f32);
+
+pub fn belt_frames(speed: f32) -> &'static [usize] {
+    if speed < 0.0 {
+        BELT_FRAMES_REVERSED
+    } else {
+        BELT_FRAMES
+    }
+}
+
+pub fn belt_animation(speed: f32) -> Animation {
+    Animation::new(belt_frames(speed), BELT_FRAME_DELAY)
+}
+
+/// Adds the speed of the conveyor a body stands on to its velocity, in place of whatever the
+/// last belt added, so stepping from belt to belt doesn't build up speed.
+fn carry_riders(
+    context: Res<RapierContext>,
+    conveyors: Query<&Conveyor>,
+    mut riders: Query<(Entity, &mut Carried, &mut Velocity)>,
+) {
+    for (rider, mut carried, mut velocity) in riders.iter_mut() {
+        let belt_speed = context
+            .contacts_with(rider)
+            .filter(|contact_pair| contact::is_standing(contact_pair, rider))
+            .find_map(|contact_pair| {
+                let other = contact::other_collider(&contact_pair, rider);
+                conveyors.get(other).ok().map(|conveyor| conveyor.speed)
+            });
+
+        match belt_speed {
+            Some(speed) => {
+                velocity.linvel.x += speed - carried.0;
+                carried.0 = speed;
+            }
+            // Leaving the belt keeps its momentum, which the rider's own movement then slows.
+            None => carried.0 = 0.0,
+        }
+    }
+}
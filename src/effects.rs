@@ -0,0 +1,122 @@
+use bevy::{
+    prelude::{
+        Color, Commands, Component, DespawnRecursiveExt, Entity, Handle, Plugin, Query, Res,
+        Transform, Update, Vec2, Vec3,
+    },
+    sprite::{Sprite, SpriteBundle, SpriteSheetBundle, TextureAtlas, TextureAtlasSprite},
+    time::{Time, Timer, TimerMode},
+};
+
+const PUFF_SECONDS: f32 = 0.3;
+const PUFF_SIZE: f32 = 0.3;
+/// Puffs spread out sideways and a little downward, from under the feet.
+const PUFF_VELOCITIES: &[Vec2] = &[
+    Vec2::new(-2.5, -0.5),
+    Vec2::new(-1.2, -1.0),
+    Vec2::new(0.0, -1.2),
+    Vec2::new(1.2, -1.0),
+    Vec2::new(2.5, -0.5),
+];
+const PUFF_GROWTH: f32 = 2.0;
+
+const AFTERIMAGE_SECONDS: f32 = 0.2;
+const AFTERIMAGE_ALPHA: f32 = 0.5;
+
+pub struct EffectsPlugin;
+
+impl Plugin for EffectsPlugin {
+    fn build(&self, app: &mut bevy::prelude::App) {
+        app.add_systems(Update, (update_particles,));
+    }
+}
+
+/// A short-lived sprite, plain or from an atlas, that drifts, grows and fades out, then
+/// despawns.
+#[derive(Component, Debug)]
+pub struct Particle {
+    timer: Timer,
+    velocity: Vec2,
+    /// Scale gained per second.
+    growth: f32,
+    start_alpha: f32,
+}
+
+/// A ring of little clouds at `position`, for the player kicking off thin air.
+pub fn spawn_puff(commands: &mut Commands, position: Vec2) {
+    for velocity in PUFF_VELOCITIES {
+        let color = Color::rgba(1.0, 1.0, 1.0, 0.8);
+        commands
+            .spawn(SpriteBundle {
+                sprite: Sprite {
+                    color,
+                    custom_size: Some(Vec2::splat(PUFF_SIZE)),
+                    ..Sprite::default()
+                },
+                // In front of the player.
+                transform: Transform::from_translation(position.extend(2.0)),
+                ..SpriteBundle::default()
+            })
+            .insert(Particle {
+                timer: Timer::from_seconds(PUFF_SECONDS, TimerMode::Once),
+                velocity: *velocity,
+                growth: PUFF_GROWTH,
+                start_alpha: color.a(),
+            });
+    }
+}
+
+/// A fading copy of a sprite left behind where it is now.
+pub fn spawn_afterimage(
+    commands: &mut Commands,
+    atlas: &Handle<TextureAtlas>,
+    sprite: &TextureAtlasSprite,
+    transform: &Transform,
+) {
+    let mut sprite = sprite.clone();
+    sprite.color.set_a(AFTERIMAGE_ALPHA);
+    let mut transform = *transform;
+    // Just behind the sprite it copies.
+    transform.translation.z -= 0.1;
+    commands
+        .spawn(SpriteSheetBundle {
+            sprite,
+            texture_atlas: atlas.clone(),
+            transform,
+            ..SpriteSheetBundle::default()
+        })
+        .insert(Particle {
+            timer: Timer::from_seconds(AFTERIMAGE_SECONDS, TimerMode::Once),
+            velocity: Vec2::ZERO,
+            growth: 0.0,
+            start_alpha: AFTERIMAGE_ALPHA,
+        });
+}
+
+fn update_particles(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut particles: Query<(
+        Entity,
+        &mut Particle,
+        &mut Transform,
+        Option<&mut Sprite>,
+        Option<&mut TextureAtlasSprite>,
+    )>,
+) {
+    for (entity, mut particle, mut transform, sprite, atlas_sprite) in particles.iter_mut() {
+        if particle.timer.tick(time.delta()).finished() {
+            commands.entity(entity).despawn_recursive();
+            continue;
+        }
+        let seconds = time.delta_seconds();
+        transform.translation += (particle.velocity * seconds).extend(0.0);
+        transform.scale += Vec3::new(particle.growth, particle.growth, 0.0) * seconds;
+        let alpha = particle.start_alpha * particle.timer.percent_left();
+        if let Some(mut sprite) = sprite {
+            sprite.color.set_a(alpha);
+        }
+        if let Some(mut sprite) = atlas_sprite {
+            sprite.color.set_a(alpha);
+        }
+    }
+}
@@ -6,6 +6,7 @@ use bevy::{
     ecs::{
         component::Component,
         entity::Entity,
+        schedule::{common_conditions::in_state, IntoSystemConfigs},
         system::{Commands, Query},
     },
     hierarchy::BuildChildren,
@@ -20,13 +21,16 @@ use bevy_rapier2d::{
     geometry::Collider,
 };
 
-use crate::level::ElevatorDefinition;
+use crate::{
+    level::{ElevatorDefinition, FeatureId},
+    GameState,
+};
 
 pub struct ElevatorPlugin;
 
 impl Plugin for ElevatorPlugin {
     fn build(&self, app: &mut bevy::prelude::App) {
-        app.add_systems(Update, (update,));
+        app.add_systems(Update, (update,).run_if(in_state(GameState::Playing)));
     }
 }
 
@@ -74,20 +78,20 @@ pub fn setup(
     };
 
     commands.entity(level).with_children(|children| {
-        children
-            .spawn(SpriteBundle {
-                sprite: Sprite {
-                    color: Color::rgba(1.0, 0.0, 0.0, 0.0),
-                    custom_size: Some(Vec2::new(ELEVATOR_WIDTH, 1.0)),
-                    ..Sprite::default()
-                },
-                transform: Transform::from_translation(Vec3::new(
-                    location.x + ELEVATOR_WIDTH / 2.0, // Add half the elevator
-                    location.y,
-                    1.0,
-                )),
-                ..SpriteBundle::default()
-            })
+        let mut platform = children.spawn(SpriteBundle {
+            sprite: Sprite {
+                color: Color::rgba(1.0, 0.0, 0.0, 0.0),
+                custom_size: Some(Vec2::new(ELEVATOR_WIDTH, 1.0)),
+                ..Sprite::default()
+            },
+            transform: Transform::from_translation(Vec3::new(
+                location.x + ELEVATOR_WIDTH / 2.0, // Add half the elevator
+                location.y,
+                1.0,
+            )),
+            ..SpriteBundle::default()
+        });
+        platform
             .insert(RigidBody::KinematicVelocityBased)
             .insert(crate::elevator::Elevator {
                 start: elevator.start_location,
@@ -96,21 +100,24 @@ pub fn setup(
             .insert(State::MovingForward)
             .insert(Velocity::linear(Vec2::default()))
             .insert(collider)
-            .insert(KinematicCharacterController::default())
-            .with_children(|children| {
-                children.spawn(SpriteSheetBundle {
-                    sprite: left_sprite.clone(),
-                    texture_atlas: ground_atlas.clone(),
-                    transform: Transform::from_translation(Vec3::new(-0.5, 0.0, 0.0)),
-                    ..SpriteSheetBundle::default()
-                });
-                children.spawn(SpriteSheetBundle {
-                    sprite: right_sprite.clone(),
-                    texture_atlas: ground_atlas.clone(),
-                    transform: Transform::from_translation(Vec3::new(0.5, 0.0, 0.0)),
-                    ..SpriteSheetBundle::default()
-                });
+            .insert(KinematicCharacterController::default());
+        if let Some(id) = &elevator.id {
+            platform.insert(FeatureId(id.clone()));
+        }
+        platform.with_children(|children| {
+            children.spawn(SpriteSheetBundle {
+                sprite: left_sprite.clone(),
+                texture_atlas: ground_atlas.clone(),
+                transform: Transform::from_translation(Vec3::new(-0.5, 0.0, 0.0)),
+                ..SpriteSheetBundle::default()
+            });
+            children.spawn(SpriteSheetBundle {
+                sprite: right_sprite.clone(),
+                texture_atlas: ground_atlas.clone(),
+                transform: Transform::from_translation(Vec3::new(0.5, 0.0, 0.0)),
+                ..SpriteSheetBundle::default()
             });
+        });
     });
 }
 
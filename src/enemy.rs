@@ -0,0 +1,171 @@
+use std::time::Duration;
+
+use bevy::{
+    prelude::{
+        in_state, BuildChildren, Commands, Component, DespawnRecursiveExt, Entity, EventReader,
+        Handle, IntoSystemConfigs, Plugin, Query, Res, Transform, Update, Vec2, With, Without,
+    },
+    sprite::{SpriteSheetBundle, TextureAtlas, TextureAtlasSprite},
+};
+use bevy_rapier2d::prelude::{
+    Collider, CollisionEvent, LockedAxes, QueryFilter, RapierContext, RigidBody, Velocity,
+};
+
+use crate::{
+    animation::Animation,
+    contact::Grounded,
+    conveyor::Carried,
+    level::EnemyDefinition,
+    player::{self, Direction, Player, RespawnQuery},
+    GameState,
+};
+
+const ENEMY_WALK_FRAMES: &[usize] = &[0, 1];
+const ENEMY_WALK_DELAY: Duration = Duration::from_millis(200);
+const DEFAULT_ENEMY_SPEED: f32 = 2.0;
+
+/// Distance from the enemy's center at which a wall makes it turn around.
+const WALL_CHECK_DISTANCE: f32 = 0.55;
+/// How far ahead of the enemy's center the floor is probed for a ledge.
+const LEDGE_CHECK_OFFSET: f32 = 0.6;
+const LEDGE_CHECK_DISTANCE: f32 = 0.6;
+
+/// The player's center must be this far above the enemy's to count as landing on it.
+const STOMP_MIN_HEIGHT: f32 = 1.25;
+/// Fraction of the jump impulse given back to the player after defeating an enemy.
+const STOMP_BOUNCE: f32 = 0.6;
+
+pub struct EnemyPlugin;
+
+impl Plugin for EnemyPlugin {
+    fn build(&self, app: &mut bevy::prelude::App) {
+        app.add_systems(
+            Update,
+            (patrol, player_touches_enemy).run_if(in_state(GameState::Playing)),
+        );
+    }
+}
+
+#[derive(Component, Debug)]
+pub struct Enemy {
+    pub speed: f32,
+}
+
+pub fn setup(
+    commands: &mut Commands,
+    enemy_atlas: &Handle<TextureAtlas>,
+    level: Entity,
+    enemy: &EnemyDefinition,
+) {
+    let mut sprite = TextureAtlasSprite::new(ENEMY_WALK_FRAMES[0]);
+    sprite.custom_size = Some(Vec2::new(1.0, 1.0));
+
+    commands.entity(level).with_children(|children| {
+        children
+            .spawn(SpriteSheetBundle {
+                sprite,
+                texture_atlas: enemy_atlas.clone(),
+                transform: Transform::from_translation(enemy.location.extend(1.0)),
+                ..SpriteSheetBundle::default()
+            })
+            .insert(Enemy {
+                speed: enemy.speed.unwrap_or(DEFAULT_ENEMY_SPEED),
+            })
+            .insert(Direction::Left)
+            .insert(Animation::new(ENEMY_WALK_FRAMES, ENEMY_WALK_DELAY))
+            .insert(RigidBody::Dynamic)
+            .insert(LockedAxes::ROTATION_LOCKED)
+            .insert(Collider::cuboid(0.5, 0.5))
+            .insert(Velocity::linear(Vec2::default()))
+            .insert(Grounded::default())
+            .insert(Carried::default());
+    });
+}
+
+fn patrol(
+    context: Res<RapierContext>,
+    mut enemies: Query<(
+        &Enemy,
+        &Grounded,
+        &Carried,
+        &Transform,
+        &mut Direction,
+        &mut Velocity,
+    )>,
+) {
+    // Only level geometry turns an enemy around, not the player or other enemies.
+    let filter = QueryFilter::exclude_dynamic();
+
+    for (enemy, grounded, carried, transform, mut direction, mut velocity) in enemies.iter_mut() {
+        let position = transform.translation.truncate();
+        let heading = match *direction {
+            Direction::Left => -1.0,
+            Direction::Right => 1.0,
+        };
+
+        if !grounded.0 {
+            continue;
+        }
+
+        let wall_ahead = context
+            .cast_ray(
+                position,
+                Vec2::new(heading, 0.0),
+                WALL_CHECK_DISTANCE,
+                true,
+                filter,
+            )
+            .is_some();
+        let ledge_ahead = context
+            .cast_ray(
+                position + Vec2::new(heading * LEDGE_CHECK_OFFSET, 0.0),
+                Vec2::NEG_Y,
+                LEDGE_CHECK_DISTANCE,
+                true,
+                filter,
+            )
+            .is_none();
+
+        let heading = if wall_ahead || ledge_ahead {
+            *direction = match *direction {
+                Direction::Left => Direction::Right,
+                Direction::Right => Direction::Left,
+            };
+            -heading
+        } else {
+            heading
+        };
+        velocity.linvel.x = heading * enemy.speed + carried.0;
+    }
+}
+
+fn player_touches_enemy(
+    mut commands: Commands,
+    mut collision_events: EventReader<CollisionEvent>,
+    enemies: Query<&Transform, (With<Enemy>, Without<Player>)>,
+    mut players: Query<RespawnQuery, With<Player>>,
+) {
+    for collision_event in collision_events.iter() {
+        let CollisionEvent::Started(e1, e2, _) = collision_event else {
+            continue;
+        };
+        let (player, enemy) = if enemies.contains(*e2) {
+            (*e1, *e2)
+        } else if enemies.contains(*e1) {
+            (*e2, *e1)
+        } else {
+            continue;
+        };
+        let (Ok(enemy_transform), Ok(mut player)) = (enemies.get(enemy), players.get_mut(player))
+        else {
+            continue;
+        };
+
+        if player.transform.translation.y - enemy_transform.translation.y > STOMP_MIN_HEIGHT {
+            commands.entity(enemy).despawn_recursive();
+            player.velocity.linvel.y = player.jumper.jump_impulse * STOMP_BOUNCE;
+        } else {
+            player::respawn(&mut player);
+        }
+    }
+}
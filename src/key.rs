@@ -0,0 +1,170 @@
+use std::collections::HashSet;
+
+use bevy::{
+    prelude::{
+        in_state, BuildChildren, Color, Commands, Component, DespawnRecursiveExt, Entity, Handle,
+        IntoSystemConfigs, Plugin, Query, Res, ResMut, Resource, Transform, Update, Vec2, Vec3,
+        With,
+    },
+    sprite::{Sprite, SpriteBundle, SpriteSheetBundle, TextureAtlas, TextureAtlasSprite},
+};
+use bevy_rapier2d::prelude::{Collider, RigidBody};
+
+use crate::{
+    level::{KeyColor, KeyDefinition, LockedDoorDefinition},
+    player::Player,
+    GameState,
+};
+
+const DEFAULT_DOOR_HEIGHT: f32 = 2.0;
+
+pub struct KeyPlugin;
+
+impl Plugin for KeyPlugin {
+    fn build(&self, app: &mut bevy::prelude::App) {
+        app.init_resource::<Inventory>().add_systems(
+            Update,
+            (collect_keys, open_unlocked_doors)
+                .chain()
+                .run_if(in_state(GameState::Playing)),
+        );
+    }
+}
+
+/// Keys the player has picked up in the current level.
+#[derive(Resource, Default, Debug)]
+pub struct Inventory {
+    pub keys: HashSet<KeyColor>,
+}
+
+#[derive(Component, Debug)]
+pub struct Key {
+    pub color: KeyColor,
+}
+
+#[derive(Component, Debug)]
+pub struct LockedDoor {
+    pub color: KeyColor,
+}
+
+fn key_sprite_index(color: KeyColor) -> usize {
+    match color {
+        KeyColor::Blue => 44,
+        KeyColor::Green => 49,
+        KeyColor::Red => 54,
+        KeyColor::Yellow => 59,
+    }
+}
+
+fn lock_sprite_index(color: KeyColor) -> usize {
+    match color {
+        KeyColor::Blue => 45,
+        KeyColor::Green => 50,
+        KeyColor::Red => 55,
+        KeyColor::Yellow => 60,
+    }
+}
+
+pub fn setup_key(
+    commands: &mut Commands,
+    tile_atlas: &Handle<TextureAtlas>,
+    level: Entity,
+    key: &KeyDefinition,
+) {
+    let mut sprite = TextureAtlasSprite::new(key_sprite_index(key.color));
+    sprite.custom_size = Some(Vec2::new(1.0, 1.0));
+
+    commands.entity(level).with_children(|children| {
+        children
+            .spawn(SpriteSheetBundle {
+                sprite,
+                texture_atlas: tile_atlas.clone(),
+                transform: Transform::from_translation(key.location.extend(1.0)),
+                ..SpriteSheetBundle::default()
+            })
+            .insert(Key { color: key.color });
+    });
+}
+
+pub fn setup_locked_door(
+    commands: &mut Commands,
+    tile_atlas: &Handle<TextureAtlas>,
+    level: Entity,
+    door: &LockedDoorDefinition,
+) {
+    let height = door.height.unwrap_or(DEFAULT_DOOR_HEIGHT);
+    let collider = Collider::cuboid(0.5, height / 2.0);
+
+    let mut sprite = TextureAtlasSprite::new(lock_sprite_index(door.color));
+    sprite.custom_size = Some(Vec2::new(1.0, 1.0));
+
+    // The collider is centered on the whole door, the blocks are laid out from its bottom.
+    let center_offset = (height - 1.0) / 2.0;
+
+    commands.entity(level).with_children(|children| {
+        children
+            .spawn(SpriteBundle {
+                sprite: Sprite {
+                    color: Color::rgba(1.0, 0.0, 0.0, 0.0),
+                    custom_size: Some(Vec2::new(1.0, height)),
+                    ..Sprite::default()
+                },
+                transform: Transform::from_translation(Vec3::new(
+                    door.location.x,
+                    door.location.y + center_offset,
+                    1.0,
+                )),
+                ..SpriteBundle::default()
+            })
+            .insert(RigidBody::Fixed)
+            .insert(collider)
+            .insert(LockedDoor { color: door.color })
+            .with_children(|children| {
+                let mut y_offset = 0.0;
+                while y_offset < height {
+                    children.spawn(SpriteSheetBundle {
+                        sprite: sprite.clone(),
+                        texture_atlas: tile_atlas.clone(),
+                        transform: Transform::from_translation(Vec3::new(
+                            0.0,
+                            y_offset - center_offset,
+                            0.0,
+                        )),
+                        ..SpriteSheetBundle::default()
+                    });
+                    y_offset += 1.0;
+                }
+            });
+    });
+}
+
+fn collect_keys(
+    mut commands: Commands,
+    mut inventory: ResMut<Inventory>,
+    keys: Query<(Entity, &Key, &Transform)>,
+    players: Query<&Transform, With<Player>>,
+) {
+    for player_transform in players.iter() {
+        for (key_entity, key, key_transform) in keys.iter() {
+            if (player_transform.translation.x - key_transform.translation.x).abs() < 0.75
+                && (player_transform.translation.y - key_transform.translation.y).abs() < 1.0
+            {
+                commands.entity(key_entity).despawn_recursive();
+                inventory.keys.insert(key.color);
+            }
+        }
+    }
+}
+
+/// Removes every door the player holds the key for, whenever it was picked up.
+fn open_unlocked_doors(
+    mut commands: Commands,
+    inventory: Res<Inventory>,
+    doors: Query<(Entity, &LockedDoor)>,
+) {
+    for (door_entity, door) in doors.iter() {
+        if inventory.keys.contains(&door.color) {
+            commands.entity(door_entity).despawn_recursive();
+        }
+    }
+}
@@ -1,23 +1,50 @@
-use std::fs::File;
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+};
+
+use anyhow::Context;
 
 use bevy::{
     prelude::{
-        AssetServer, Assets, BuildChildren, Children, Color, Commands, Component,
-        DespawnRecursiveExt, Entity, Handle, Image, Input, KeyCode, Plugin, Query, Res, ResMut,
-        SpatialBundle, Startup, Transform, Update, Vec2, Vec3, Without,
+        in_state, AssetServer, Assets, BuildChildren, ClearColor, Color, Commands, Component,
+        DespawnRecursiveExt, Entity, Event, EventReader, EventWriter, Handle, Image, Input,
+        IntoSystemConfigs, KeyCode, NextState, OnEnter, Plugin, Query, Res, ResMut, SpatialBundle,
+        Startup, Transform, Update, Vec2, Vec3, With,
     },
     sprite::{Sprite, SpriteBundle, SpriteSheetBundle, TextureAtlas, TextureAtlasSprite},
 };
 use bevy_rapier2d::prelude::{Collider, RigidBody};
 
-use crate::player::Player;
+use crate::{
+    conveyor,
+    key::Inventory,
+    player::{Abilities, Player, RespawnQuery},
+    progress::{LevelCompleted, LevelTimer},
+    transition::{Fade, FadeDirection},
+    GameState,
+};
 
 pub struct LevelPlugin;
 
 #[derive(Debug, Component)]
 pub struct Level {
-    idx: u8,
+    pub idx: u8,
 }
+
+/// Leave the current level for the one at `destination`.
+#[derive(Debug, Event)]
+pub struct ChangeLevel {
+    pub destination: u8,
+}
+
+/// Rebuild the current level from its definition and put the player back at the start.
+#[derive(Debug, Event)]
+pub struct RestartLevel;
+
+/// Name given to a feature in the world file, so triggers can refer to it.
+#[derive(Debug, Component)]
+pub struct FeatureId(pub String);
 #[derive(Debug, Component)]
 pub struct Ready;
 
@@ -28,24 +55,171 @@ fn setup_level(mut commands: Commands) {
     //.insert(Shader::from_glsl("shaders/bg.glsl", ShaderStage::Fragment, ));
 }
 
+const WORLD_FILE: &str = "world.yml";
+
+/// What the menus need to know about a level without loading it.
+#[derive(Debug)]
+pub struct LevelSummary {
+    pub id: String,
+    pub name: String,
+}
+
+/// Every level in the world, in the order they are played.
+pub fn list_levels() -> anyhow::Result<Vec<LevelSummary>> {
+    let world = WorldDefinition::load(WORLD_FILE)?;
+    Ok(world
+        .levels
+        .into_iter()
+        .map(|level| LevelSummary {
+            name: level.name.unwrap_or_else(|| level.id.clone()),
+            id: level.id,
+        })
+        .collect())
+}
+
 #[derive(serde::Deserialize)]
 struct WorldDefinition {
+    /// World or level files, relative to this one. Their levels come after this file's own.
+    #[serde(default)]
+    include: Vec<PathBuf>,
+    #[serde(default)]
     levels: Vec<LevelDefinition>,
+    /// Abilities the player has in every level of the world.
+    #[serde(default)]
+    abilities: Vec<Ability>,
+}
+
+impl WorldDefinition {
+    /// Reads the world at `path` along with every file it includes, flattened into one list of
+    /// levels.
+    fn load(path: impl AsRef<Path>) -> anyhow::Result<WorldDefinition> {
+        let mut world = WorldDefinition {
+            include: Vec::new(),
+            levels: Vec::new(),
+            abilities: Vec::new(),
+        };
+        collect_levels(
+            path.as_ref(),
+            &mut world,
+            &mut HashMap::new(),
+            &mut Vec::new(),
+        )?;
+        Ok(world)
+    }
+}
+
+/// Appends the levels and abilities defined in `path` and its includes to `world`.
+///
+/// `level_files` maps each level id seen so far to the file defining it, and `including` holds
+/// the chain of files that led to `path`, to catch include cycles.
+fn collect_levels(
+    path: &Path,
+    world: &mut WorldDefinition,
+    level_files: &mut HashMap<String, PathBuf>,
+    including: &mut Vec<PathBuf>,
+) -> anyhow::Result<()> {
+    // The same file can be reached by differently spelled paths, like `sub/../world.yml`.
+    let path = path
+        .canonicalize()
+        .with_context(|| format!("couldn't find {}", path.display()))?;
+    let path = path.as_path();
+    if including.iter().any(|included| included == path) {
+        anyhow::bail!("{} includes itself", path.display());
+    }
+    let text = std::fs::read_to_string(path)
+        .with_context(|| format!("couldn't read {}", path.display()))?;
+    let value: serde_yaml::Value = serde_yaml::from_str(&text)
+        .with_context(|| format!("invalid YAML in {}", path.display()))?;
+
+    // A file holding a single level has its features at the top.
+    let (file_levels, includes) = if value.get("features").is_some() {
+        let level: LevelDefinition = serde_yaml::from_str(&text)
+            .with_context(|| format!("invalid level in {}", path.display()))?;
+        (vec![level], Vec::new())
+    } else {
+        let file_world: WorldDefinition = serde_yaml::from_str(&text)
+            .with_context(|| format!("invalid world in {}", path.display()))?;
+        world.abilities.extend(file_world.abilities);
+        (file_world.levels, file_world.include)
+    };
+
+    for level in file_levels {
+        if let Some(previous) = level_files.insert(level.id.clone(), path.to_path_buf()) {
+            anyhow::bail!(
+                "duplicate level id `{}` in {}, already defined in {}",
+                level.id,
+                path.display(),
+                previous.display()
+            );
+        }
+        world.levels.push(level);
+    }
+
+    let directory = path.parent().unwrap_or(Path::new(""));
+    including.push(path.to_path_buf());
+    for include in includes {
+        collect_levels(&directory.join(include), world, level_files, including)?;
+    }
+    including.pop();
+    Ok(())
 }
 
 #[derive(serde::Deserialize)]
 struct LevelDefinition {
-    // id: String,
-    // name: String,
+    id: String,
+    /// Shown on the level select screen. Defaults to the id.
+    name: Option<String>,
+    /// Color behind everything else, as `[r, g, b]`.
+    clear_color: Option<[f32; 3]>,
+    #[serde(default)]
+    backgrounds: Vec<BackgroundDefinition>,
+    /// Abilities the player has in this level, on top of the world's.
+    #[serde(default)]
+    abilities: Vec<Ability>,
     features: Vec<Feature>,
 }
 
+/// Something the player can do beyond running and jumping, granted by the world or a level.
+#[derive(serde::Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum Ability {
+    /// Slide down walls and jump off them.
+    WallJump,
+    /// Jump again in mid-air.
+    AirJump,
+    /// Burst forward with Shift.
+    Dash,
+}
+
+#[derive(serde::Deserialize, Debug)]
+pub struct BackgroundDefinition {
+    pub image: String,
+    /// Size of one copy of the image, in tiles.
+    pub size: Vec2,
+    /// 0.0 scrolls with the level, 1.0 stays fixed on screen.
+    pub parallax: f32,
+    /// Tile the image horizontally so it never runs out.
+    #[serde(default)]
+    pub repeat: bool,
+    #[serde(default)]
+    pub offset: Vec2,
+    pub z: Option<f32>,
+}
+
 #[derive(serde::Deserialize)]
 #[serde(rename_all = "snake_case", tag = "kind", content = "data")]
 enum Feature {
     Floor(Vec<FloorDefinition>),
     Exit(Vec<ExitDefinition>),
     Elevator(Vec<ElevatorDefinition>),
+    Water(Vec<WaterDefinition>),
+    Key(Vec<KeyDefinition>),
+    LockedDoor(Vec<LockedDoorDefinition>),
+    Enemy(Vec<EnemyDefinition>),
+    Decoration(Vec<DecorationDefinition>),
+    Sign(Vec<SignDefinition>),
+    Trigger(Vec<TriggerDefinition>),
+    AbilityPickup(Vec<AbilityPickupDefinition>),
 }
 
 #[derive(serde::Deserialize)]
@@ -54,6 +228,7 @@ struct ExitDefinition {
 }
 #[derive(serde::Deserialize, Debug)]
 pub struct ElevatorDefinition {
+    pub id: Option<String>,
     pub start_location: Vec2,
     pub end_y: f32,
     //pub path: tg::Line,
@@ -67,19 +242,143 @@ pub enum ElevatorControl {
     Switches { locations: (Vec2, Vec2) },
 }
 
+#[derive(serde::Deserialize, Debug)]
+pub struct WaterDefinition {
+    /// Center of the bottom-left water tile.
+    pub loc: Vec2,
+    /// Width and height of the zone, in tiles.
+    pub size: Vec2,
+}
+
+#[derive(serde::Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum KeyColor {
+    Blue,
+    Green,
+    Red,
+    Yellow,
+}
+
+#[derive(serde::Deserialize, Debug)]
+pub struct KeyDefinition {
+    pub location: Vec2,
+    pub color: KeyColor,
+}
+
+#[derive(serde::Deserialize, Debug)]
+pub struct LockedDoorDefinition {
+    /// Center of the bottom block of the door.
+    pub location: Vec2,
+    pub color: KeyColor,
+    /// Number of blocks stacked upward from `location`. Defaults to 2, the player's height.
+    pub height: Option<f32>,
+}
+
+#[derive(serde::Deserialize, Debug)]
+pub struct EnemyDefinition {
+    pub location: Vec2,
+    pub speed: Option<f32>,
+}
+
+#[derive(serde::Deserialize)]
+struct DecorationDefinition {
+    location: Vec2,
+    sprite: usize,
+    #[serde(default)]
+    atlas: DecorationAtlas,
+    /// Draw order relative to the player, who is at 1.0. Defaults to behind the player.
+    z: Option<f32>,
+}
+
+#[derive(serde::Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+enum DecorationAtlas {
+    #[default]
+    Tiles,
+    Ground,
+}
+
+#[derive(serde::Deserialize, Debug)]
+pub struct SignDefinition {
+    pub location: Vec2,
+    pub text: String,
+}
+
+/// Grants `ability` for the rest of the level once the player touches it.
+#[derive(serde::Deserialize, Debug)]
+pub struct AbilityPickupDefinition {
+    pub location: Vec2,
+    pub ability: Ability,
+}
+
+#[derive(serde::Deserialize, Debug)]
+pub struct TriggerDefinition {
+    /// Center of the bottom-left tile of the region.
+    pub loc: Vec2,
+    /// Width and height of the region, in tiles.
+    pub size: Vec2,
+    #[serde(default)]
+    pub on_enter: Vec<Action>,
+    #[serde(default)]
+    pub on_exit: Vec<Action>,
+    /// Run every frame while the player is inside.
+    #[serde(default)]
+    pub on_stay: Vec<Action>,
+}
+
+#[derive(serde::Deserialize, Debug, Clone)]
+#[serde(rename_all = "snake_case", tag = "kind")]
+pub enum Action {
+    ShowText {
+        text: String,
+        seconds: Option<f32>,
+    },
+    StartElevator {
+        id: String,
+    },
+    StopElevator {
+        id: String,
+    },
+    /// Hide a floor and disable its collider, or bring it back.
+    ToggleBlock {
+        id: String,
+    },
+    CameraZoom {
+        scale: f32,
+    },
+    PlaySound {
+        path: String,
+    },
+    EndLevel,
+}
+
 #[derive(serde::Deserialize)]
 struct FloorDefinition {
+    id: Option<String>,
     loc: Vec2,
     length: f32,
     left: Option<usize>,
     right: Option<usize>,
     middle: Option<usize>,
+    /// Surface velocity applied to anything standing on the floor. Positive moves right.
+    conveyor: Option<f32>,
 }
 
 impl Plugin for LevelPlugin {
     fn build(&self, app: &mut bevy::prelude::App) {
-        app.add_systems(Startup, setup_level)
-            .add_systems(Update, (exit_level, spawn_level));
+        app.add_event::<ChangeLevel>()
+            .add_event::<RestartLevel>()
+            .add_systems(Startup, setup_level)
+            .add_systems(
+                OnEnter(GameState::LoadingLevel),
+                (unload_level, spawn_level).chain(),
+            )
+            .add_systems(
+                Update,
+                (exit_level, restart_on_key).run_if(in_state(GameState::Playing)),
+            )
+            // Levels are changed from gameplay as well as from the pause and level select menus.
+            .add_systems(Update, (change_level, restart_level));
     }
 }
 
@@ -88,20 +387,35 @@ struct Exit {
     destination: u8,
 }
 
+#[allow(clippy::too_many_arguments)]
 fn spawn_level(
-    query: Query<(Entity, &Level), Without<Children>>,
+    query: Query<(Entity, &Level)>,
+    mut player_query: Query<(RespawnQuery, &mut Abilities), With<Player>>,
     mut commands: Commands,
     mut atlases: ResMut<Assets<TextureAtlas>>,
+    mut inventory: ResMut<Inventory>,
+    mut level_timer: ResMut<LevelTimer>,
+    mut fade: ResMut<Fade>,
+    mut next_state: ResMut<NextState<GameState>>,
     server: Res<AssetServer>,
 ) {
-    if query.is_empty() {
+    if query.is_empty() || player_query.is_empty() {
         return;
     }
     let (entity, level) = query.single();
-    let mut file = std::io::BufReader::new(File::open("world.yml").unwrap());
-    let world_definition: WorldDefinition = serde_yaml::from_reader(&mut file).unwrap();
+    let (mut player, mut abilities) = player_query.single_mut();
+    crate::player::respawn(&mut player);
+    *inventory = Inventory::default();
+    let world_definition = WorldDefinition::load(WORLD_FILE).unwrap();
     match world_definition.levels.get(level.idx as usize) {
         Some(level_definition) => {
+            *level_timer = LevelTimer::new(level_definition.id.clone());
+            abilities.0 = world_definition
+                .abilities
+                .iter()
+                .chain(&level_definition.abilities)
+                .copied()
+                .collect();
             spawn_level_features(
                 &mut commands,
                 &mut atlases,
@@ -110,13 +424,13 @@ fn spawn_level(
                 level_definition,
                 &server,
             );
+            fade.start(FadeDirection::In);
+            next_state.set(GameState::LevelTransition);
         }
-        None => spawn_win_screen(),
+        None => next_state.set(GameState::WorldComplete),
     }
 }
 
-fn spawn_win_screen() {}
-
 fn spawn_level_features(
     commands: &mut Commands,
     atlases: &mut Assets<TextureAtlas>,
@@ -148,6 +462,21 @@ fn spawn_level_features(
     );
     let ground_atlas = atlases.add(ground_atlas);
 
+    let enemy_spritesheet: Handle<Image> = server.load("Spritesheets/spritesheet_enemies.png");
+    let enemy_atlas =
+        TextureAtlas::from_grid(enemy_spritesheet, Vec2::new(128., 128.), 8, 8, None, None);
+    let enemy_atlas = atlases.add(enemy_atlas);
+
+    let clear_color = level_definition
+        .clear_color
+        .map(|[r, g, b]| Color::rgb(r, g, b))
+        .unwrap_or(crate::DEFAULT_CLEAR_COLOR);
+    commands.insert_resource(ClearColor(clear_color));
+
+    for background in &level_definition.backgrounds {
+        super::background::setup(commands, server, level_entity, background);
+    }
+
     for feature in &level_definition.features {
         match feature {
             Feature::Exit(exits) => {
@@ -159,6 +488,24 @@ fn spawn_level_features(
             Feature::Elevator(elevators) => {
                 spawn_elevators(commands, &ground_atlas, level_entity, elevators)
             }
+            Feature::Water(waters) => spawn_waters(commands, &tile_atlas, level_entity, waters),
+            Feature::Key(keys) => spawn_keys(commands, &tile_atlas, level_entity, keys),
+            Feature::LockedDoor(doors) => {
+                spawn_locked_doors(commands, &tile_atlas, level_entity, doors)
+            }
+            Feature::Enemy(enemies) => spawn_enemies(commands, &enemy_atlas, level_entity, enemies),
+            Feature::Decoration(decorations) => spawn_decorations(
+                commands,
+                &tile_atlas,
+                &ground_atlas,
+                level_entity,
+                decorations,
+            ),
+            Feature::Sign(signs) => spawn_signs(commands, &tile_atlas, level_entity, signs),
+            Feature::Trigger(triggers) => spawn_triggers(commands, level_entity, triggers),
+            Feature::AbilityPickup(pickups) => {
+                spawn_ability_pickups(commands, &tile_atlas, level_entity, pickups)
+            }
         }
     }
 }
@@ -174,6 +521,109 @@ fn spawn_elevators(
     }
 }
 
+fn spawn_waters(
+    commands: &mut Commands,
+    tile_atlas: &Handle<TextureAtlas>,
+    level_entity: Entity,
+    waters: &[WaterDefinition],
+) {
+    for water in waters {
+        super::water::setup(commands, tile_atlas, level_entity, water);
+    }
+}
+
+fn spawn_keys(
+    commands: &mut Commands,
+    tile_atlas: &Handle<TextureAtlas>,
+    level_entity: Entity,
+    keys: &[KeyDefinition],
+) {
+    for key in keys {
+        super::key::setup_key(commands, tile_atlas, level_entity, key);
+    }
+}
+
+fn spawn_locked_doors(
+    commands: &mut Commands,
+    tile_atlas: &Handle<TextureAtlas>,
+    level_entity: Entity,
+    doors: &[LockedDoorDefinition],
+) {
+    for door in doors {
+        super::key::setup_locked_door(commands, tile_atlas, level_entity, door);
+    }
+}
+
+fn spawn_enemies(
+    commands: &mut Commands,
+    enemy_atlas: &Handle<TextureAtlas>,
+    level_entity: Entity,
+    enemies: &[EnemyDefinition],
+) {
+    for enemy in enemies {
+        super::enemy::setup(commands, enemy_atlas, level_entity, enemy);
+    }
+}
+
+fn spawn_triggers(commands: &mut Commands, level_entity: Entity, triggers: &[TriggerDefinition]) {
+    for trigger in triggers {
+        super::trigger::setup(commands, level_entity, trigger);
+    }
+}
+
+fn spawn_ability_pickups(
+    commands: &mut Commands,
+    tile_atlas: &Handle<TextureAtlas>,
+    level_entity: Entity,
+    pickups: &[AbilityPickupDefinition],
+) {
+    for pickup in pickups {
+        super::ability::setup(commands, tile_atlas, level_entity, pickup);
+    }
+}
+
+fn spawn_signs(
+    commands: &mut Commands,
+    tile_atlas: &Handle<TextureAtlas>,
+    level_entity: Entity,
+    signs: &[SignDefinition],
+) {
+    for sign in signs {
+        super::sign::setup(commands, tile_atlas, level_entity, sign);
+    }
+}
+
+fn spawn_decorations(
+    commands: &mut Commands,
+    tile_atlas: &Handle<TextureAtlas>,
+    ground_atlas: &Handle<TextureAtlas>,
+    level_entity: Entity,
+    decorations: &[DecorationDefinition],
+) {
+    const DECORATION_DEFAULT_Z: f32 = 0.5;
+
+    commands.entity(level_entity).with_children(|children| {
+        for decoration in decorations {
+            let mut sprite = TextureAtlasSprite::new(decoration.sprite);
+            sprite.custom_size = Some(Vec2::new(1.0, 1.0));
+            let texture_atlas = match decoration.atlas {
+                DecorationAtlas::Tiles => tile_atlas.clone(),
+                DecorationAtlas::Ground => ground_atlas.clone(),
+            };
+            let translation = decoration
+                .location
+                .extend(decoration.z.unwrap_or(DECORATION_DEFAULT_Z));
+
+            children.spawn(SpriteSheetBundle {
+                sprite,
+                texture_atlas,
+                transform: Transform::from_translation(translation),
+                ..SpriteSheetBundle::default()
+            });
+        }
+    });
+}
+
 fn spawn_exits(
     commands: &mut Commands,
     tile_atlas: &Handle<TextureAtlas>,
@@ -237,37 +687,80 @@ fn spawn_exits(
     }
 }
 
-fn exit_level(
-    mut commands: Commands,
+pub fn exit_level(
     keyboard_input: Res<Input<KeyCode>>,
     exit_query: Query<(&Exit, &Transform)>,
-    mut player_query: Query<(&Player, &mut Transform), Without<Exit>>,
-    mut level_query: Query<(Entity, &mut Level)>,
+    player_query: Query<&Transform, With<Player>>,
+    mut change_level: EventWriter<ChangeLevel>,
+    mut level_completed: EventWriter<LevelCompleted>,
 ) {
     if keyboard_input.pressed(KeyCode::Up) {
         if player_query.is_empty() {
             return;
         }
-        if level_query.is_empty() {
-            return;
-        }
-        let (_, mut player_transform) = player_query.single_mut();
+        let player_transform = player_query.single();
         for (exit, exit_transform) in exit_query.iter() {
             if (player_transform.translation.x - exit_transform.translation.x).abs() < 0.5
                 && (player_transform.translation.y - exit_transform.translation.y).abs() < 0.5
             {
-                println!("Go to level: {}", exit.destination);
-                player_transform.translation = Vec3::new(0.0, 10.0, 1.0);
-                let (level_entity, mut level) = level_query.single_mut();
-                level.idx = exit.destination;
-                commands.entity(level_entity).despawn_descendants();
-                commands.entity(level_entity).clear_children();
-                println!("level: {level:?}");
+                change_level.send(ChangeLevel {
+                    destination: exit.destination,
+                });
+                level_completed.send(LevelCompleted);
             }
         }
     }
 }
 
+fn change_level(
+    mut change_level: EventReader<ChangeLevel>,
+    mut level_query: Query<&mut Level>,
+    mut fade: ResMut<Fade>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    let Some(event) = change_level.iter().last() else {
+        return;
+    };
+    if level_query.is_empty() {
+        return;
+    }
+    println!("Go to level: {}", event.destination);
+    let mut level = level_query.single_mut();
+    level.idx = event.destination;
+    fade.start(FadeDirection::Out);
+    next_state.set(GameState::LevelTransition);
+    println!("level: {level:?}");
+}
+
+fn restart_on_key(
+    keyboard_input: Res<Input<KeyCode>>,
+    mut restart_level: EventWriter<RestartLevel>,
+) {
+    if keyboard_input.just_pressed(KeyCode::R) {
+        restart_level.send(RestartLevel);
+    }
+}
+
+fn restart_level(
+    mut restart_level: EventReader<RestartLevel>,
+    mut fade: ResMut<Fade>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    if restart_level.iter().last().is_none() {
+        return;
+    }
+    fade.start(FadeDirection::Out);
+    next_state.set(GameState::LevelTransition);
+}
+
+/// Despawns the previous level once the screen is dark, so `spawn_level` starts from nothing.
+fn unload_level(mut commands: Commands, level_query: Query<Entity, With<Level>>) {
+    for level_entity in level_query.iter() {
+        commands.entity(level_entity).despawn_descendants();
+        commands.entity(level_entity).clear_children();
+    }
+}
+
 fn spawn_floors(
     commands: &mut Commands,
     floors: &[FloorDefinition],
@@ -296,7 +789,9 @@ fn spawn_floor_onto(
 
     let mut entity_builder = commands.entity(entity);
     while x_offset < floor.length {
-        let mut ground_sprite = if floor.length == 1.0 {
+        let mut ground_sprite = if let Some(speed) = floor.conveyor {
+            TextureAtlasSprite::new(conveyor::belt_frames(speed)[0])
+        } else if floor.length == 1.0 {
             TextureAtlasSprite::new(floor.left.unwrap_or(GROUND_TEXTURE_INDEX_ALONE))
         } else if x_offset == 0.0 {
             TextureAtlasSprite::new(floor.left.unwrap_or(GROUND_TEXTURE_INDEX_LEFT))
@@ -311,43 +806,197 @@ fn spawn_floor_onto(
         let translation = location.extend(1.0);
 
         entity_builder.with_children(|children| {
-            children.spawn(SpriteSheetBundle {
+            let mut tile = children.spawn(SpriteSheetBundle {
                 sprite: ground_sprite.clone(),
                 texture_atlas: ground_atlas.clone(),
                 transform: Transform::from_translation(translation),
                 ..SpriteSheetBundle::default()
             });
+            if let Some(speed) = floor.conveyor {
+                tile.insert(conveyor::belt_animation(speed));
+            }
+            if let Some(id) = &floor.id {
+                tile.insert(FeatureId(id.clone()));
+            }
         });
         x_offset += 1.0;
     }
     entity_builder.with_children(|children| {
-        children
-            .spawn(SpriteBundle {
-                sprite: Sprite {
-                    color: Color::rgba(1.0, 0.0, 0.0, 0.0),
-                    custom_size: Some(Vec2::new(floor.length, 1.0)),
-                    ..Sprite::default()
-                },
-                transform: Transform::from_translation(Vec3::new(
-                    floor.loc.x + floor.length / 2.,
-                    floor.loc.y,
-                    1.0,
-                )),
-                ..SpriteBundle::default()
-            })
-            .insert(RigidBody::Fixed)
-            .insert(collider);
+        let mut floor_collider = children.spawn(SpriteBundle {
+            sprite: Sprite {
+                color: Color::rgba(1.0, 0.0, 0.0, 0.0),
+                custom_size: Some(Vec2::new(floor.length, 1.0)),
+                ..Sprite::default()
+            },
+            transform: Transform::from_translation(Vec3::new(
+                floor.loc.x + floor.length / 2.,
+                floor.loc.y,
+                1.0,
+            )),
+            ..SpriteBundle::default()
+        });
+        floor_collider.insert(RigidBody::Fixed).insert(collider);
+        if let Some(speed) = floor.conveyor {
+            floor_collider.insert(conveyor::Conveyor { speed });
+        }
+        if let Some(id) = &floor.id {
+            floor_collider.insert(FeatureId(id.clone()));
+        }
     });
 }
 
 #[cfg(test)]
 mod tests {
-    use super::WorldDefinition;
-    use std::{fs::File, io::BufReader};
+    use super::{Ability, Action, Feature, WorldDefinition};
+    use crate::test_dir::TestDir;
+    use bevy::prelude::Vec2;
+    use std::fs;
 
     #[test]
     fn deserialize_world() {
-        let mut file = BufReader::new(File::open("world.yml").unwrap());
-        let _world_definition: WorldDefinition = serde_yaml::from_reader(&mut file).unwrap();
+        let _world_definition = WorldDefinition::load("world.yml").unwrap();
+    }
+
+    /// Writes `files` into a fresh directory.
+    fn world_dir(name: &str, files: &[(&str, &str)]) -> TestDir {
+        let dir = TestDir::new(name);
+        for (file, contents) in files {
+            let path = dir.join(file);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, contents).unwrap();
+        }
+        dir
+    }
+
+    #[test]
+    fn load_world_with_includes() {
+        let dir = world_dir(
+            "includes",
+            &[
+                (
+                    "world.yml",
+                    "include: [levels/second.yml, more.yml]\nlevels:\n  - id: first\n    features: []\n",
+                ),
+                ("levels/second.yml", "id: second\nfeatures: []\n"),
+                ("more.yml", "levels:\n  - id: third\n    features: []\n"),
+            ],
+        );
+        let world_definition = WorldDefinition::load(dir.join("world.yml")).unwrap();
+        let ids: Vec<_> = world_definition
+            .levels
+            .iter()
+            .map(|level| level.id.as_str())
+            .collect();
+        assert_eq!(ids, ["first", "second", "third"]);
+    }
+
+    #[test]
+    fn load_world_abilities() {
+        let dir = world_dir(
+            "abilities",
+            &[
+                (
+                    "world.yml",
+                    "include: [more.yml]\nlevels:\n  - id: first\n    features: []\n",
+                ),
+                (
+                    "more.yml",
+                    "abilities: [wall_jump]\nlevels:\n  - id: second\n    abilities: [wall_jump]\n    features: []\n",
+                ),
+            ],
+        );
+        let world_definition = WorldDefinition::load(dir.join("world.yml")).unwrap();
+        assert_eq!(world_definition.abilities, [Ability::WallJump]);
+        assert!(world_definition.levels[0].abilities.is_empty());
+        assert_eq!(world_definition.levels[1].abilities, [Ability::WallJump]);
+    }
+
+    #[test]
+    fn load_world_rejects_duplicate_ids() {
+        let dir = world_dir(
+            "duplicates",
+            &[
+                (
+                    "world.yml",
+                    "include: [copy.yml]\nlevels:\n  - id: first\n    features: []\n",
+                ),
+                ("copy.yml", "id: first\nfeatures: []\n"),
+            ],
+        );
+        let error = WorldDefinition::load(dir.join("world.yml")).unwrap_err();
+        assert!(error.to_string().contains("duplicate level id `first`"));
+    }
+
+    #[test]
+    fn load_world_rejects_include_cycles() {
+        let dir = world_dir(
+            "cycle",
+            &[
+                (
+                    "world.yml",
+                    "include: [sub/../world.yml]\nlevels:\n  - id: first\n    features: []\n",
+                ),
+                ("sub/other.yml", "levels: []\n"),
+            ],
+        );
+        let error = WorldDefinition::load(dir.join("world.yml")).unwrap_err();
+        assert!(error.to_string().contains("includes itself"));
+    }
+
+    #[test]
+    fn deserialize_water() {
+        let yaml = r#"
+levels:
+  - id: water
+    features:
+      - kind: water
+        data:
+          - loc: [4, -2]
+            size: [6, 3]
+"#;
+        let world_definition: WorldDefinition = serde_yaml::from_str(yaml).unwrap();
+        match &world_definition.levels[0].features[0] {
+            Feature::Water(waters) => assert_eq!(waters[0].size, Vec2::new(6.0, 3.0)),
+            _ => panic!("expected a water feature"),
+        }
+    }
+
+    #[test]
+    fn deserialize_trigger_actions() {
+        let yaml = r#"
+levels:
+  - id: trigger
+    features:
+      - kind: elevator
+        data:
+          - id: lift
+            start_location: [27, -3]
+            end_y: 25
+            control:
+              kind: constant
+      - kind: trigger
+        data:
+          - loc: [20, -2]
+            size: [2, 3]
+            on_enter:
+              - kind: stop_elevator
+                id: lift
+              - kind: show_text
+                text: Hello
+            on_exit:
+              - kind: end_level
+"#;
+        let world_definition: WorldDefinition = serde_yaml::from_str(yaml).unwrap();
+        match &world_definition.levels[0].features[1] {
+            Feature::Trigger(triggers) => {
+                assert!(matches!(
+                    &triggers[0].on_enter[0],
+                    Action::StopElevator { id } if id == "lift"
+                ));
+                assert!(matches!(triggers[0].on_exit[0], Action::EndLevel));
+                assert!(triggers[0].on_stay.is_empty());
+            }
+            _ => panic!("expected a trigger feature"),
+        }
     }
 }
@@ -1,27 +1,72 @@
+use std::time::Duration;
+
 use bevy::{
-    core_pipeline::clear_color::ClearColorConfig,
+    asset::ChangeWatcher,
     prelude::*,
     render::camera::ScalingMode,
     window::{PrimaryWindow, WindowResolution},
 };
-use bevy_rapier2d::prelude::{NoUserData, RapierPhysicsPlugin};
+use bevy_rapier2d::prelude::{NoUserData, RapierConfiguration, RapierPhysicsPlugin};
 
+mod ability;
 mod animation;
+mod background;
+mod contact;
+mod conveyor;
+mod effects;
 mod elevator;
+mod enemy;
+mod key;
 mod level;
+mod menu;
 mod player;
+mod player_config;
+mod progress;
+mod save;
+mod sign;
+#[cfg(test)]
+mod test_dir;
+mod transition;
+mod trigger;
+mod water;
+
+pub const DEFAULT_CLEAR_COLOR: Color = Color::rgb(0.0, 0.2, 0.3);
+/// Scale of the camera transform when no trigger has zoomed it.
+pub const CAMERA_SCALE: f32 = 4.0;
+
+#[derive(States, Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum GameState {
+    #[default]
+    MainMenu,
+    /// The previous level is despawned and the current one built from the world file on entry.
+    LoadingLevel,
+    /// Fading to or from black around `LoadingLevel`, with the level frozen.
+    LevelTransition,
+    Playing,
+    Paused,
+    LevelSelect,
+    WorldComplete,
+}
 
 fn main() {
-    let plugins = DefaultPlugins.set(WindowPlugin {
-        primary_window: Some(Window {
-            resolution: WindowResolution::new(800.0, 480.0),
-            title: "Platformer!".into(),
-            ..Window::default()
-        }),
-        ..WindowPlugin::default()
-    });
+    let plugins = DefaultPlugins
+        .set(WindowPlugin {
+            primary_window: Some(Window {
+                resolution: WindowResolution::new(800.0, 480.0),
+                title: "Platformer!".into(),
+                ..Window::default()
+            }),
+            ..WindowPlugin::default()
+        })
+        .set(AssetPlugin {
+            // Picks up edits to the player config while the game runs.
+            watch_for_changes: ChangeWatcher::with_delay(Duration::from_millis(200)),
+            ..AssetPlugin::default()
+        });
     App::new()
-        .add_systems(Startup, (configure_window,))
+        .insert_resource(ClearColor(DEFAULT_CLEAR_COLOR))
+        .add_state::<GameState>()
+        .add_systems(Startup, (configure_window, pause_physics))
         .add_plugins((
             plugins,
             RapierPhysicsPlugin::<NoUserData>::default(),
@@ -29,11 +74,37 @@ fn main() {
             animation::AnimationPlugin,
             level::LevelPlugin,
             elevator::ElevatorPlugin,
+            water::WaterPlugin,
+            conveyor::ConveyorPlugin,
+            key::KeyPlugin,
+            background::BackgroundPlugin,
+            enemy::EnemyPlugin,
+            sign::SignPlugin,
+            trigger::TriggerPlugin,
+            contact::ContactPlugin,
+            ability::AbilityPlugin,
+        ))
+        .add_plugins((
+            menu::MenuPlugin,
+            progress::ProgressPlugin,
+            transition::TransitionPlugin,
+            effects::EffectsPlugin,
+            player_config::PlayerConfigPlugin,
         ))
-        .add_systems(Update, bevy::window::close_on_esc)
+        .add_systems(OnEnter(GameState::Playing), resume_physics)
+        .add_systems(OnExit(GameState::Playing), pause_physics)
         .run();
 }
 
+fn resume_physics(mut rapier_configuration: ResMut<RapierConfiguration>) {
+    rapier_configuration.physics_pipeline_active = true;
+}
+
+/// Keeps the player from falling through the world while nothing is there to stand on.
+fn pause_physics(mut rapier_configuration: ResMut<RapierConfiguration>) {
+    rapier_configuration.physics_pipeline_active = false;
+}
+
 fn configure_window(mut query: Query<&mut Window, With<PrimaryWindow>>) {
     if let Ok(mut window) = query.get_single_mut() {
         window.title = "Platformer!".into();
@@ -42,16 +113,15 @@ fn configure_window(mut query: Query<&mut Window, With<PrimaryWindow>>) {
 
 fn new_camera_2d() -> Camera2dBundle {
     let mut cam2d = Camera2dBundle {
-        camera_2d: Camera2d {
-            clear_color: ClearColorConfig::Custom(Color::rgb(0.0, 0.2, 0.3)),
-        },
         projection: OrthographicProjection {
             scaling_mode: ScalingMode::FixedHorizontal(10.0),
+            // The camera sits at z=1000 in front of the player; this keeps sprites below z=0 visible.
+            far: 2000.0,
             ..OrthographicProjection::default()
         },
         ..Camera2dBundle::default()
     };
-    cam2d.transform.scale = Vec3::new(4.0, 4.0, 1.0);
+    cam2d.transform.scale = Vec3::new(CAMERA_SCALE, CAMERA_SCALE, 1.0);
     println!("{:?}", cam2d.transform);
     cam2d
 }
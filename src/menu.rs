@@ -0,0 +1,320 @@
+use bevy::{
+    app::AppExit,
+    prelude::{
+        in_state, BuildChildren, Bundle, Button, ButtonBundle, Changed, ChildBuilder, Color,
+        Commands, Component, Condition, DespawnRecursiveExt, Entity, EventWriter, Input,
+        IntoSystemConfigs, KeyCode, NextState, NodeBundle, OnEnter, OnExit, Plugin, Query, Res,
+        ResMut, Resource, State, TextBundle, Time, Update, With,
+    },
+    text::TextStyle,
+    ui::{
+        AlignItems, BackgroundColor, FlexDirection, Interaction, JustifyContent, Style, UiRect, Val,
+    },
+};
+
+use crate::{
+    level::{self, ChangeLevel, Level, LevelSummary, RestartLevel},
+    progress::Progress,
+    GameState,
+};
+
+const BUTTON_COLOR: Color = Color::rgb(0.15, 0.15, 0.15);
+const BUTTON_HOVERED_COLOR: Color = Color::rgb(0.3, 0.3, 0.3);
+const LOCKED_TEXT_COLOR: Color = Color::rgb(0.5, 0.5, 0.5);
+
+pub struct MenuPlugin;
+
+impl Plugin for MenuPlugin {
+    fn build(&self, app: &mut bevy::prelude::App) {
+        app.insert_resource(LevelSelectReturn(GameState::MainMenu))
+            .add_systems(OnEnter(GameState::MainMenu), spawn_main_menu)
+            .add_systems(Update, start_game.run_if(in_state(GameState::MainMenu)))
+            .add_systems(OnExit(GameState::MainMenu), despawn_screen)
+            .add_systems(OnEnter(GameState::WorldComplete), spawn_win_screen)
+            .add_systems(
+                Update,
+                play_again.run_if(in_state(GameState::WorldComplete)),
+            )
+            .add_systems(OnExit(GameState::WorldComplete), despawn_screen)
+            .add_systems(
+                Update,
+                toggle_pause
+                    .run_if(in_state(GameState::Playing).or_else(in_state(GameState::Paused))),
+            )
+            .add_systems(OnEnter(GameState::Paused), (spawn_pause_menu, pause_time))
+            .add_systems(Update, pause_buttons.run_if(in_state(GameState::Paused)))
+            .add_systems(OnExit(GameState::Paused), (despawn_screen, resume_time))
+            .add_systems(OnEnter(GameState::LevelSelect), spawn_level_select)
+            .add_systems(
+                Update,
+                level_select_buttons.run_if(in_state(GameState::LevelSelect)),
+            )
+            .add_systems(OnExit(GameState::LevelSelect), despawn_screen)
+            .add_systems(Update, highlight_buttons);
+    }
+}
+
+/// Root node of whichever full-screen menu is showing.
+#[derive(Component)]
+struct Screen;
+
+/// What a button on the pause menu does when pressed.
+#[derive(Component, Debug, Clone, Copy)]
+enum PauseButton {
+    Resume,
+    Restart,
+    LevelSelect,
+    Quit,
+}
+
+#[derive(Component, Debug, Clone, Copy)]
+enum LevelSelectButton {
+    Level(u8),
+    Back,
+}
+
+/// The state the level select screen goes back to: the main menu or the pause menu.
+#[derive(Resource, Debug)]
+struct LevelSelectReturn(GameState);
+
+fn spawn_screen(commands: &mut Commands, title: &str, subtitle: &str) -> Entity {
+    commands
+        .spawn(NodeBundle {
+            style: Style {
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                flex_direction: FlexDirection::Column,
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                row_gap: Val::Px(24.0),
+                ..Style::default()
+            },
+            background_color: BackgroundColor(Color::rgba(0.0, 0.0, 0.0, 0.6)),
+            ..NodeBundle::default()
+        })
+        .insert(Screen)
+        .with_children(|children| {
+            children.spawn(TextBundle::from_section(
+                title,
+                TextStyle {
+                    font_size: 64.0,
+                    color: Color::WHITE,
+                    ..TextStyle::default()
+                },
+            ));
+            children.spawn(TextBundle::from_section(
+                subtitle,
+                TextStyle {
+                    font_size: 24.0,
+                    color: Color::WHITE,
+                    ..TextStyle::default()
+                },
+            ));
+        })
+        .id()
+}
+
+fn button_style() -> Style {
+    Style {
+        width: Val::Px(360.0),
+        padding: UiRect::all(Val::Px(12.0)),
+        justify_content: JustifyContent::Center,
+        ..Style::default()
+    }
+}
+
+fn button_text(label: &str, color: Color) -> TextBundle {
+    TextBundle::from_section(
+        label,
+        TextStyle {
+            font_size: 28.0,
+            color,
+            ..TextStyle::default()
+        },
+    )
+}
+
+fn spawn_button(children: &mut ChildBuilder, label: &str, action: impl Bundle) {
+    children
+        .spawn(ButtonBundle {
+            style: button_style(),
+            background_color: BackgroundColor(BUTTON_COLOR),
+            ..ButtonBundle::default()
+        })
+        .insert(action)
+        .with_children(|children| {
+            children.spawn(button_text(label, Color::WHITE));
+        });
+}
+
+fn despawn_screen(mut commands: Commands, screens: Query<Entity, With<Screen>>) {
+    for screen in screens.iter() {
+        commands.entity(screen).despawn_recursive();
+    }
+}
+
+fn spawn_main_menu(mut commands: Commands) {
+    spawn_screen(
+        &mut commands,
+        "Platformer!",
+        "Press Enter to start, or L to choose a level",
+    );
+}
+
+fn start_game(
+    keyboard_input: Res<Input<KeyCode>>,
+    mut level_select_return: ResMut<LevelSelectReturn>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    if keyboard_input.just_pressed(KeyCode::Return) {
+        next_state.set(GameState::LoadingLevel);
+    } else if keyboard_input.just_pressed(KeyCode::L) {
+        level_select_return.0 = GameState::MainMenu;
+        next_state.set(GameState::LevelSelect);
+    }
+}
+
+fn spawn_win_screen(mut commands: Commands) {
+    spawn_screen(&mut commands, "You win!", "Press Enter to play again");
+}
+
+fn play_again(
+    keyboard_input: Res<Input<KeyCode>>,
+    mut levels: Query<&mut Level>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    if keyboard_input.just_pressed(KeyCode::Return) {
+        for mut level in levels.iter_mut() {
+            level.idx = 0;
+        }
+        next_state.set(GameState::LoadingLevel);
+    }
+}
+
+fn toggle_pause(
+    keyboard_input: Res<Input<KeyCode>>,
+    state: Res<State<GameState>>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    if !keyboard_input.just_pressed(KeyCode::Escape) {
+        return;
+    }
+    match state.get() {
+        GameState::Playing => next_state.set(GameState::Paused),
+        GameState::Paused => next_state.set(GameState::Playing),
+        _ => {}
+    }
+}
+
+/// Freezes timers and animations; physics is already stopped on leaving `Playing`.
+fn pause_time(mut time: ResMut<Time>) {
+    time.pause();
+}
+
+fn resume_time(mut time: ResMut<Time>) {
+    time.unpause();
+}
+
+fn spawn_pause_menu(mut commands: Commands) {
+    let screen = spawn_screen(&mut commands, "Paused", "Press Esc to resume");
+    commands.entity(screen).with_children(|children| {
+        spawn_button(children, "Resume", PauseButton::Resume);
+        spawn_button(children, "Restart level", PauseButton::Restart);
+        spawn_button(children, "Level select", PauseButton::LevelSelect);
+        spawn_button(children, "Quit", PauseButton::Quit);
+    });
+}
+
+fn pause_buttons(
+    buttons: Query<(&Interaction, &PauseButton), Changed<Interaction>>,
+    mut level_select_return: ResMut<LevelSelectReturn>,
+    mut next_state: ResMut<NextState<GameState>>,
+    mut restart_level: EventWriter<RestartLevel>,
+    mut app_exit: EventWriter<AppExit>,
+) {
+    for (interaction, button) in buttons.iter() {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+        match button {
+            PauseButton::Resume => next_state.set(GameState::Playing),
+            PauseButton::Restart => restart_level.send(RestartLevel),
+            PauseButton::LevelSelect => {
+                level_select_return.0 = GameState::Paused;
+                next_state.set(GameState::LevelSelect);
+            }
+            PauseButton::Quit => app_exit.send(AppExit),
+        }
+    }
+}
+
+fn highlight_buttons(
+    mut buttons: Query<(&Interaction, &mut BackgroundColor), (Changed<Interaction>, With<Button>)>,
+) {
+    for (interaction, mut color) in buttons.iter_mut() {
+        color.0 = match interaction {
+            Interaction::Hovered | Interaction::Pressed => BUTTON_HOVERED_COLOR,
+            Interaction::None => BUTTON_COLOR,
+        };
+    }
+}
+
+fn level_label(progress: &Progress, levels: &[LevelSummary], idx: usize) -> String {
+    let level = &levels[idx];
+    let status = if !progress.is_unlocked(levels, idx) {
+        " (locked)".to_string()
+    } else if let Some(best) = progress.best_times.get(&level.id) {
+        format!(" - best {:.1}s", best.as_secs_f32())
+    } else if progress.completed.contains(&level.id) {
+        " - completed".to_string()
+    } else {
+        String::new()
+    };
+    format!("{}. {}{status}", idx + 1, level.name)
+}
+
+fn spawn_level_select(mut commands: Commands, progress: Res<Progress>) {
+    let levels = level::list_levels().unwrap();
+    let screen = spawn_screen(&mut commands, "Select a level", "Press Esc to go back");
+    commands.entity(screen).with_children(|children| {
+        for idx in 0..levels.len() {
+            let label = level_label(&progress, &levels, idx);
+            if progress.is_unlocked(&levels, idx) {
+                spawn_button(children, &label, LevelSelectButton::Level(idx as u8));
+            } else {
+                children
+                    .spawn(NodeBundle {
+                        style: button_style(),
+                        background_color: BackgroundColor(BUTTON_COLOR),
+                        ..NodeBundle::default()
+                    })
+                    .with_children(|children| {
+                        children.spawn(button_text(&label, LOCKED_TEXT_COLOR));
+                    });
+            }
+        }
+        spawn_button(children, "Back", LevelSelectButton::Back);
+    });
+}
+
+fn level_select_buttons(
+    keyboard_input: Res<Input<KeyCode>>,
+    buttons: Query<(&Interaction, &LevelSelectButton), Changed<Interaction>>,
+    level_select_return: Res<LevelSelectReturn>,
+    mut next_state: ResMut<NextState<GameState>>,
+    mut change_level: EventWriter<ChangeLevel>,
+) {
+    if keyboard_input.just_pressed(KeyCode::Escape) {
+        next_state.set(level_select_return.0);
+        return;
+    }
+    for (interaction, button) in buttons.iter() {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+        match button {
+            LevelSelectButton::Level(idx) => change_level.send(ChangeLevel { destination: *idx }),
+            LevelSelectButton::Back => next_state.set(level_select_return.0),
+        }
+    }
+}
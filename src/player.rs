@@ -1,18 +1,30 @@
-use std::time::{Duration, Instant};
+use std::{collections::HashSet, time::Duration};
 
 use bevy::{
+    ecs::query::WorldQuery,
     prelude::{
-        AssetServer, Assets, BuildChildren, Commands, Component, Entity, EventReader, Handle,
-        Image, Input, KeyCode, Plugin, Query, Res, ResMut, Startup, Transform, Update, Vec2, Vec3,
-        With, Without,
+        in_state, AssetServer, Assets, BuildChildren, Commands, Component, Entity, Handle, Image,
+        Input, IntoSystemConfigs, KeyCode, Plugin, Query, Res, ResMut, Startup, Transform, Update,
+        Vec2, Vec3, With, Without,
     },
-    sprite::{SpriteSheetBundle, TextureAtlas, TextureAtlasSprite},
+    sprite::{Anchor, SpriteSheetBundle, TextureAtlas, TextureAtlasSprite},
+    time::{Time, Timer, TimerMode},
 };
 use bevy_rapier2d::prelude::{
-    ActiveEvents, Collider, CollisionEvent, Damping, LockedAxes, RigidBody, Velocity,
+    ActiveEvents, Collider, Damping, GravityScale, LockedAxes, QueryFilter, RapierContext,
+    RigidBody, Velocity,
 };
 
-use crate::animation::Animation;
+use crate::{
+    animation::Animation,
+    contact::{GroundVelocity, Grounded, WallContact},
+    conveyor::Carried,
+    effects,
+    level::Ability,
+    player_config::PlayerConfig,
+    water::WATER_GRAVITY_SCALE,
+    GameState,
+};
 
 const SPRITESHEET: &str = "Spritesheets/spritesheet_players.png";
 const SPRITESHEET_COLS: usize = 7;
@@ -23,19 +35,96 @@ const SPRITE_TILE_HEIGHT: f32 = 256.;
 const SPRITE_IDX_GREEN_STAND: usize = 5;
 const SPRITE_IDX_GREEN_WALK_0: usize = 11;
 const SPRITE_IDX_GREEN_WALK_1: usize = 18;
+const SPRITE_IDX_GREEN_JUMP: usize = 25;
+const SPRITE_IDX_GREEN_DUCK: usize = 12;
 
 const WALK_CYCLE_DELAY: Duration = Duration::from_millis(120);
 const RUN_CYCLE_DELAY: Duration = Duration::from_millis(40);
+/// How long the player keeps running after the run key is let go.
+const RUN_HOLD_TIME: Duration = Duration::from_millis(100);
+
+/// How much faster than the ground under it the player may still be rising and count as landed,
+/// as the solver leaves a body riding a platform slightly out of step with it.
+const LANDING_SPEED_TOLERANCE: f32 = 0.1;
+
+/// Fastest the player falls while holding toward a wall.
+const WALL_SLIDE_SPEED: f32 = 3.0;
+/// Horizontal speed of the kick away from a wall.
+const WALL_JUMP_SPEED: f32 = 8.0;
+/// Fraction of the jump impulse a wall jump gives.
+const WALL_JUMP_LIFT: f32 = 0.8;
+/// How long after a wall jump the player can't steer, so holding toward the wall doesn't
+/// cancel the kick.
+const WALL_JUMP_LOCK: Duration = Duration::from_millis(150);
+
+/// Half the height of the standing and crouching colliders. Both are half a tile wide.
+const STANDING_HALF_HEIGHT: f32 = 1.0;
+const CROUCHING_HALF_HEIGHT: f32 = 0.5;
+const CROUCH_SPEED_FACTOR: f32 = 0.4;
+/// Keeps the stand-up check from hitting the floor and walls the player is touching.
+const HEADROOM_CHECK_MARGIN: f32 = 0.05;
+
+const DASH_DISTANCE: f32 = 4.0;
+const DASH_TIME: Duration = Duration::from_millis(150);
+const DASH_COOLDOWN: Duration = Duration::from_millis(600);
+const DASH_AFTERIMAGE_INTERVAL: Duration = Duration::from_millis(30);
+
+pub const SPAWN_POINT: Vec3 = Vec3::new(0.0, 10.0, 1.0);
 
 #[derive(Component)]
 pub struct Player {
     pub speed: f32,
-    pub last_running: Instant,
+    /// Multiplies `speed` while running.
+    pub run_multiplier: f32,
+    /// Runs from the last frame the run key was held; the player runs until it finishes.
+    pub run_timer: Timer,
+    /// Horizontal acceleration towards the input direction, in units per second squared.
+    pub ground_acceleration: f32,
+    pub air_acceleration: f32,
+    /// Horizontal slowdown with no input, or when going faster than the input asks for.
+    pub ground_deceleration: f32,
+    pub air_deceleration: f32,
+    /// Used instead of acceleration when the input points against the current motion.
+    pub turn_acceleration: f32,
+    /// Out of water.
+    pub linear_damping: f32,
 }
 
 impl Player {
+    fn new(config: &PlayerConfig) -> Player {
+        Player {
+            speed: config.speed,
+            run_multiplier: config.run_multiplier,
+            run_timer: expired_timer(RUN_HOLD_TIME),
+            ground_acceleration: config.ground_acceleration,
+            air_acceleration: config.air_acceleration,
+            ground_deceleration: config.ground_deceleration,
+            air_deceleration: config.air_deceleration,
+            turn_acceleration: config.turn_acceleration,
+            linear_damping: config.linear_damping,
+        }
+    }
+
+    fn update_running(&mut self, delta: Duration, is_run_pressed: bool) {
+        if is_run_pressed {
+            self.run_timer.reset();
+        } else {
+            self.run_timer.tick(delta);
+        }
+    }
+
     fn is_running(&self) -> bool {
-        self.last_running.elapsed() < Duration::from_millis(100)
+        !self.run_timer.finished()
+    }
+}
+
+/// Abilities the player has in the current level.
+#[derive(Component, Debug, Default)]
+pub struct Abilities(pub HashSet<Ability>);
+
+impl Abilities {
+    pub fn has(&self, ability: Ability) -> bool {
+        self.0.contains(&ability)
     }
 }
 
@@ -48,13 +137,19 @@ impl Plugin for PlayerPlugin {
             (
                 player_jumps,
                 jump_reset,
+                // A dash turns gravity off from the frame it starts.
+                apply_jump_gravity.after(player_dashes),
+                wall_slide,
+                player_dashes,
+                player_crouches,
                 check_reset_game,
                 player_movement,
                 apply_movement_animation,
                 apply_idle_sprite,
                 update_direction,
                 update_sprite_direction,
-            ),
+            )
+                .run_if(in_state(GameState::Playing)),
         );
     }
 }
@@ -62,7 +157,69 @@ impl Plugin for PlayerPlugin {
 #[derive(Debug, Component)]
 pub struct Jumper {
     pub jump_impulse: f32,
+    /// Letting go of jump early caps the upward speed at this fraction of `jump_impulse`.
+    pub jump_release_factor: f32,
     pub is_jumping: bool,
+    /// Upward velocity of a single swim stroke while in water.
+    pub swim_impulse: f32,
+    pub is_swimming: bool,
+    /// Runs from the moment the player leaves the ground; a jump is still allowed until it
+    /// finishes.
+    pub coyote_time: Timer,
+    /// Runs from the last press of the jump key; landing before it finishes jumps right away.
+    pub jump_buffer: Timer,
+    /// Runs after a wall jump; horizontal input is ignored until it finishes.
+    pub wall_jump_lock: Timer,
+    /// Jumps allowed in mid-air with the air jump ability, refilled on landing.
+    pub air_jumps: u8,
+    pub air_jumps_left: u8,
+    /// Gravity is multiplied by this while falling, for a quicker drop than rise.
+    pub fall_gravity_multiplier: f32,
+    /// Near the top of a jump, while the vertical speed is below `apex_hang_speed`, gravity is
+    /// multiplied by `apex_gravity_multiplier` instead, for a short hang.
+    pub apex_hang_speed: f32,
+    pub apex_gravity_multiplier: f32,
+    /// Fastest the player can fall.
+    pub terminal_fall_speed: f32,
+}
+
+/// A short burst of speed in the facing direction, with the dash ability.
+#[derive(Debug, Component)]
+pub struct Dasher {
+    /// How far a dash carries the player, covered over the length of `dash_time`.
+    pub distance: f32,
+    pub dash_time: Duration,
+    /// Distance still to cover while dashing.
+    remaining: Option<f32>,
+    /// Runs from the start of a dash; the next dash waits for it to finish.
+    pub cooldown: Timer,
+    /// Only one dash is allowed between leaving the ground and landing again.
+    pub has_air_dash: bool,
+    heading: f32,
+    afterimage: Timer,
+}
+
+impl Dasher {
+    pub fn is_dashing(&self) -> bool {
+        self.remaining.is_some()
+    }
+
+    fn speed(&self) -> f32 {
+        self.distance / self.dash_time.as_secs_f32()
+    }
+}
+
+/// Whether the player is ducking under something, with a half-height collider.
+#[derive(Debug, Component, Default)]
+pub struct Crouch {
+    pub is_crouching: bool,
+}
+
+/// A timer that has already run out, for windows that should start closed.
+fn expired_timer(duration: Duration) -> Timer {
+    let mut timer = Timer::new(duration, TimerMode::Once);
+    timer.tick(duration);
+    timer
 }
 
 pub fn setup(
@@ -81,26 +238,26 @@ pub fn setup(
     );
 
     let atlas_handle = atlases.add(texture_atlas);
-    let collider = Collider::cuboid(0.5, 1.0);
+    // Until the config file has loaded.
+    let config = PlayerConfig::default();
+    let collider = Collider::cuboid(0.5, STANDING_HALF_HEIGHT);
     let mut sprite = TextureAtlasSprite::new(SPRITE_IDX_GREEN_STAND);
     sprite.custom_size = Some(Vec2::new(1.0, 2.0));
     commands
         .spawn(SpriteSheetBundle {
             sprite,
             texture_atlas: atlas_handle,
-            transform: Transform::from_translation(Vec3::new(0.0, 10.0, 1.0)),
+            transform: Transform::from_translation(SPAWN_POINT),
             ..SpriteSheetBundle::default()
         })
         .insert(Direction::Right)
-        .insert(Player {
-            speed: 5.5,
-            last_running: Instant::now() - Duration::from_secs_f32(1.0),
-        })
+        .insert(Player::new(&config))
         .insert(RigidBody::Dynamic)
         .insert(Damping {
-            linear_damping: 0.75,
+            linear_damping: config.linear_damping,
             angular_damping: 1.0,
         })
+        .insert(GravityScale(1.0))
         .insert(LockedAxes::ROTATION_LOCKED)
         .insert(collider)
         .insert(ActiveEvents::COLLISION_EVENTS)
@@ -109,96 +266,458 @@ pub fn setup(
             angvel: 0.0,
         })
         .insert(Jumper {
-            jump_impulse: 50.0,
+            jump_impulse: config.jump_impulse,
+            jump_release_factor: config.jump_release_factor,
             is_jumping: false,
+            swim_impulse: config.swim_impulse,
+            is_swimming: false,
+            coyote_time: expired_timer(Duration::from_secs_f32(config.coyote_time)),
+            jump_buffer: expired_timer(Duration::from_secs_f32(config.jump_buffer_time)),
+            wall_jump_lock: expired_timer(WALL_JUMP_LOCK),
+            air_jumps: config.air_jumps,
+            air_jumps_left: 0,
+            fall_gravity_multiplier: config.fall_gravity_multiplier,
+            apex_hang_speed: config.apex_hang_speed,
+            apex_gravity_multiplier: config.apex_gravity_multiplier,
+            terminal_fall_speed: config.terminal_fall_speed,
         })
+        .insert(Grounded::default())
+        .insert(GroundVelocity::default())
+        .insert(WallContact::default())
+        .insert(Carried::default())
+        .insert(Dasher {
+            distance: DASH_DISTANCE,
+            dash_time: DASH_TIME,
+            remaining: None,
+            cooldown: expired_timer(DASH_COOLDOWN),
+            has_air_dash: true,
+            heading: 1.0,
+            afterimage: Timer::new(DASH_AFTERIMAGE_INTERVAL, TimerMode::Repeating),
+        })
+        .insert(Crouch::default())
+        .insert(Abilities::default())
         .with_children(|children| {
             children.spawn(crate::new_camera_2d());
         });
 }
 
 pub fn player_jumps(
+    mut commands: Commands,
     keyboard_input: Res<Input<KeyCode>>,
-    mut players: Query<(&mut Jumper, &mut Velocity), With<Player>>,
+    time: Res<Time>,
+    mut players: Query<
+        (
+            Entity,
+            &mut Jumper,
+            &Grounded,
+            &WallContact,
+            &Abilities,
+            &Transform,
+            &mut Velocity,
+            &mut TextureAtlasSprite,
+        ),
+        With<Player>,
+    >,
 ) {
-    for (mut jumper, mut velocity) in players.iter_mut() {
-        if keyboard_input.just_pressed(KeyCode::Space) && !jumper.is_jumping {
+    for (
+        entity,
+        mut jumper,
+        grounded,
+        wall_contact,
+        abilities,
+        transform,
+        mut velocity,
+        mut sprite,
+    ) in players.iter_mut()
+    {
+        if jumper.is_swimming {
+            // Every press is a new stroke, so the player can swim upward indefinitely.
+            if keyboard_input.just_pressed(KeyCode::Space) {
+                velocity.linvel.y = jumper.swim_impulse;
+                jumper.is_jumping = true;
+            }
+            continue;
+        }
+        if grounded.0 && !jumper.is_jumping {
+            jumper.coyote_time.reset();
+        } else {
+            jumper.coyote_time.tick(time.delta());
+        }
+        if keyboard_input.just_pressed(KeyCode::Space) {
+            jumper.jump_buffer.reset();
+        } else {
+            jumper.jump_buffer.tick(time.delta());
+        }
+
+        if !jumper.jump_buffer.finished() && !jumper.coyote_time.finished() {
             eprintln!("player jumps: {}", velocity.linvel.y);
             velocity.linvel.y = jumper.jump_impulse;
             jumper.is_jumping = true;
+            // Both windows are spent on this jump.
+            jumper.jump_buffer = expired_timer(jumper.jump_buffer.duration());
+            jumper.coyote_time = expired_timer(jumper.coyote_time.duration());
+        } else if let Some(wall) = wall_contact.0.filter(|_| {
+            !jumper.jump_buffer.finished() && !grounded.0 && abilities.has(Ability::WallJump)
+        }) {
+            let away = match wall {
+                Direction::Left => 1.0,
+                Direction::Right => -1.0,
+            };
+            velocity.linvel =
+                Vec2::new(away * WALL_JUMP_SPEED, jumper.jump_impulse * WALL_JUMP_LIFT);
+            jumper.is_jumping = true;
+            jumper.jump_buffer = expired_timer(jumper.jump_buffer.duration());
+            jumper.wall_jump_lock.reset();
+        } else if keyboard_input.just_pressed(KeyCode::Space)
+            && !grounded.0
+            && abilities.has(Ability::AirJump)
+            && jumper.air_jumps_left > 0
+        {
+            velocity.linvel.y = jumper.jump_impulse;
+            jumper.is_jumping = true;
+            jumper.air_jumps_left -= 1;
+            // Don't jump again on landing.
+            jumper.jump_buffer = expired_timer(jumper.jump_buffer.duration());
+
+            // Hold the jump frame until landing.
+            commands.entity(entity).remove::<Animation>();
+            sprite.index = SPRITE_IDX_GREEN_JUMP;
+            let feet = transform.translation.truncate() - Vec2::new(0.0, 1.0);
+            effects::spawn_puff(&mut commands, feet);
         }
         if keyboard_input.just_released(KeyCode::Space)
             && jumper.is_jumping
             && velocity.linvel.y > 0.
         {
             eprintln!("player release: {}", velocity.linvel.y);
-            velocity.linvel.y = velocity.linvel.y.min(jumper.jump_impulse * 0.4);
+            velocity.linvel.y = velocity
+                .linvel
+                .y
+                .min(jumper.jump_impulse * jumper.jump_release_factor);
             eprintln!("new velocity: {}", velocity.linvel.y);
             // If the input was released, slow the jump
         }
     }
 }
 
-fn jump_reset(
-    mut query: Query<(Entity, &mut Jumper)>,
-    mut collision_events: EventReader<CollisionEvent>,
+fn jump_reset(mut query: Query<(&mut Jumper, &Grounded, &GroundVelocity, &Velocity)>) {
+    for (mut jumper, grounded, ground_velocity, velocity) in query.iter_mut() {
+        // Contacts lag a frame behind, so the ground the player just jumped off still counts.
+        // Moving up with a rising elevator isn't a jump, though.
+        if grounded.0 && velocity.linvel.y <= ground_velocity.0.y + LANDING_SPEED_TOLERANCE {
+            jumper.is_jumping = false;
+            jumper.air_jumps_left = jumper.air_jumps;
+        }
+    }
+}
+
+/// Owns the player's gravity scale: dashes, water, the apex of a jump and falling each get their
+/// own, and falls are capped at the terminal speed.
+fn apply_jump_gravity(
+    mut players: Query<
+        (
+            &Jumper,
+            &Dasher,
+            &Grounded,
+            &mut GravityScale,
+            &mut Velocity,
+        ),
+        With<Player>,
+    >,
 ) {
-    for collision_event in collision_events.iter() {
-        for (entity, mut jumper) in query.iter_mut() {
-            set_jumping_false_if_touching_floor(&entity, &mut jumper, collision_event);
+    for (jumper, dasher, grounded, mut gravity_scale, mut velocity) in players.iter_mut() {
+        let vy = velocity.linvel.y;
+        let scale = if dasher.is_dashing() {
+            0.0
+        } else if jumper.is_swimming {
+            WATER_GRAVITY_SCALE
+        } else if grounded.0 {
+            1.0
+        } else if jumper.is_jumping && vy.abs() < jumper.apex_hang_speed {
+            jumper.apex_gravity_multiplier
+        } else if vy < 0.0 {
+            jumper.fall_gravity_multiplier
+        } else {
+            1.0
+        };
+        if gravity_scale.0 != scale {
+            gravity_scale.0 = scale;
+        }
+        if vy < -jumper.terminal_fall_speed {
+            velocity.linvel.y = -jumper.terminal_fall_speed;
         }
     }
 }
 
-fn set_jumping_false_if_touching_floor(
-    entity: &Entity,
-    jumper: &mut Jumper,
-    collision_event: &CollisionEvent,
+fn wall_slide(
+    keyboard_input: Res<Input<KeyCode>>,
+    mut players: Query<(&Abilities, &Grounded, &WallContact, &mut Velocity), With<Player>>,
 ) {
-    if let CollisionEvent::Started(e1, e2, _) = collision_event {
-        if entity == e1 || entity == e2 {
-            jumper.is_jumping = false;
+    for (abilities, grounded, wall_contact, mut velocity) in players.iter_mut() {
+        if !abilities.has(Ability::WallJump) || grounded.0 {
+            continue;
+        }
+        let is_holding_toward_wall = match wall_contact.0 {
+            Some(Direction::Left) => keyboard_input.pressed(KeyCode::Left),
+            Some(Direction::Right) => keyboard_input.pressed(KeyCode::Right),
+            None => false,
+        };
+        if is_holding_toward_wall && velocity.linvel.y < -WALL_SLIDE_SPEED {
+            velocity.linvel.y = -WALL_SLIDE_SPEED;
         }
     }
 }
 
-fn check_reset_game(mut query: Query<(&Player, &mut Velocity, &mut Transform)>) {
-    for (_, mut velocity, mut transform) in query.iter_mut() {
-        if transform.translation.y < -200.0 {
-            transform.translation = Vec3::new(0.0, 10.0, 1.0);
+#[allow(clippy::type_complexity)]
+fn player_dashes(
+    mut commands: Commands,
+    keyboard_input: Res<Input<KeyCode>>,
+    time: Res<Time>,
+    mut players: Query<
+        (
+            &mut Dasher,
+            &Abilities,
+            &Grounded,
+            &Direction,
+            &Transform,
+            &mut Velocity,
+            &TextureAtlasSprite,
+            &Handle<TextureAtlas>,
+        ),
+        With<Player>,
+    >,
+) {
+    for (mut dasher, abilities, grounded, direction, transform, mut velocity, sprite, atlas) in
+        players.iter_mut()
+    {
+        dasher.cooldown.tick(time.delta());
+        if grounded.0 {
+            dasher.has_air_dash = true;
+        }
+
+        if !dasher.is_dashing()
+            && keyboard_input.just_pressed(KeyCode::ShiftLeft)
+            && abilities.has(Ability::Dash)
+            && dasher.cooldown.finished()
+            && (grounded.0 || dasher.has_air_dash)
+        {
+            if !grounded.0 {
+                dasher.has_air_dash = false;
+            }
+            dasher.heading = match direction {
+                Direction::Left => -1.0,
+                Direction::Right => 1.0,
+            };
+            dasher.remaining = Some(dasher.distance);
+            dasher.cooldown.reset();
+            dasher.afterimage.reset();
+        }
+
+        let Some(remaining) = dasher.remaining else {
+            continue;
+        };
+        let seconds = time.delta_seconds();
+        if remaining <= 0.0 {
+            // The whole distance was covered by last frame's step.
+            dasher.remaining = None;
             velocity.linvel.x = 0.0;
-            velocity.linvel.y = 0.0;
+        } else if seconds > 0.0 {
+            // Cover exactly the distance left on the last step, whatever the frame rate.
+            let step = (dasher.speed() * seconds).min(remaining);
+            dasher.remaining = Some(remaining - step);
+            // Level flight: gravity is off while dashing, and this cancels any fall speed.
+            velocity.linvel = Vec2::new(dasher.heading * step / seconds, 0.0);
+            if dasher.afterimage.tick(time.delta()).just_finished() {
+                effects::spawn_afterimage(&mut commands, atlas, sprite, transform);
+            }
+        }
+    }
+}
+
+#[allow(clippy::type_complexity)]
+fn player_crouches(
+    mut commands: Commands,
+    keyboard_input: Res<Input<KeyCode>>,
+    context: Res<RapierContext>,
+    mut players: Query<
+        (
+            Entity,
+            &mut Crouch,
+            &Grounded,
+            &mut Transform,
+            &mut Collider,
+            &mut TextureAtlasSprite,
+        ),
+        With<Player>,
+    >,
+) {
+    for (entity, mut crouch, grounded, mut transform, mut collider, mut sprite) in
+        players.iter_mut()
+    {
+        let wants_to_crouch =
+            keyboard_input.pressed(KeyCode::Down) && (grounded.0 || crouch.is_crouching);
+        // The collider shrinks and grows around its center, so move the body to keep its
+        // feet where they are.
+        let feet_offset = STANDING_HALF_HEIGHT - CROUCHING_HALF_HEIGHT;
+
+        if wants_to_crouch && !crouch.is_crouching {
+            crouch.is_crouching = true;
+            *collider = Collider::cuboid(0.5, CROUCHING_HALF_HEIGHT);
+            transform.translation.y -= feet_offset;
+            // Draw the full-height sprite from the feet up, rather than around the body.
+            sprite.anchor =
+                Anchor::Custom(Vec2::new(0.0, -feet_offset / (2.0 * STANDING_HALF_HEIGHT)));
+            sprite.index = SPRITE_IDX_GREEN_DUCK;
+            commands.entity(entity).remove::<Animation>();
+        } else if !wants_to_crouch && crouch.is_crouching {
+            let standing_center = transform.translation.truncate() + Vec2::new(0.0, feet_offset);
+            let standing_shape = Collider::cuboid(
+                0.5 - HEADROOM_CHECK_MARGIN,
+                STANDING_HALF_HEIGHT - HEADROOM_CHECK_MARGIN,
+            );
+            let filter = QueryFilter::default()
+                .exclude_collider(entity)
+                .exclude_sensors();
+            if context
+                .intersection_with_shape(standing_center, 0.0, &standing_shape, filter)
+                .is_some()
+            {
+                // Something is in the way; stay down until there is room.
+                continue;
+            }
+
+            crouch.is_crouching = false;
+            *collider = Collider::cuboid(0.5, STANDING_HALF_HEIGHT);
+            transform.translation.y += feet_offset;
+            sprite.anchor = Anchor::Center;
+            sprite.index = SPRITE_IDX_GREEN_STAND;
+        }
+    }
+}
+
+fn check_reset_game(mut query: Query<RespawnQuery, With<Player>>) {
+    for mut player in query.iter_mut() {
+        if player.transform.translation.y < -200.0 {
+            respawn(&mut player);
         }
     }
 }
 
+/// Everything about the player that a respawn resets.
+#[derive(WorldQuery)]
+#[world_query(mutable)]
+pub struct RespawnQuery {
+    pub transform: &'static mut Transform,
+    pub velocity: &'static mut Velocity,
+    pub jumper: &'static mut Jumper,
+    pub dasher: &'static mut Dasher,
+    pub crouch: &'static mut Crouch,
+    pub carried: &'static mut Carried,
+    pub collider: &'static mut Collider,
+    pub sprite: &'static mut TextureAtlasSprite,
+}
+
+/// Puts the player back at the start of the level, at rest and standing, with no jump, dash or
+/// wall-jump lock carried over from before.
+pub fn respawn(player: &mut RespawnQueryItem) {
+    player.transform.translation = SPAWN_POINT;
+    player.velocity.linvel = Vec2::ZERO;
+    player.carried.0 = 0.0;
+
+    let jumper = &mut *player.jumper;
+    jumper.is_jumping = false;
+    jumper.air_jumps_left = 0;
+    jumper.coyote_time = expired_timer(jumper.coyote_time.duration());
+    jumper.jump_buffer = expired_timer(jumper.jump_buffer.duration());
+    jumper.wall_jump_lock = expired_timer(jumper.wall_jump_lock.duration());
+
+    let dasher = &mut *player.dasher;
+    dasher.remaining = None;
+    dasher.cooldown = expired_timer(dasher.cooldown.duration());
+    dasher.has_air_dash = true;
+
+    if player.crouch.is_crouching {
+        player.crouch.is_crouching = false;
+        *player.collider = Collider::cuboid(0.5, STANDING_HALF_HEIGHT);
+        player.sprite.anchor = Anchor::Center;
+        player.sprite.index = SPRITE_IDX_GREEN_STAND;
+    }
+}
+
 fn player_movement(
     keyboard_input: Res<Input<KeyCode>>,
-    mut players: Query<(&mut Player, &mut Velocity)>,
+    time: Res<Time>,
+    mut players: Query<(
+        &mut Player,
+        &mut Jumper,
+        &Dasher,
+        &Crouch,
+        &Grounded,
+        &Carried,
+        &mut Velocity,
+    )>,
 ) {
-    for (mut player, mut velocity) in players.iter_mut() {
-        if keyboard_input.pressed(KeyCode::B) {
-            player.last_running = Instant::now()
+    for (mut player, mut jumper, dasher, crouch, grounded, carried, mut velocity) in
+        players.iter_mut()
+    {
+        player.update_running(time.delta(), keyboard_input.pressed(KeyCode::B));
+        if !jumper.wall_jump_lock.tick(time.delta()).finished() || dasher.is_dashing() {
+            continue;
         }
-        let running_coeff = if player.is_running() { 3.0 } else { 1.0 };
-        if keyboard_input.pressed(KeyCode::Left) {
-            velocity.linvel.x = -player.speed * running_coeff;
+        let running_coeff = if crouch.is_crouching {
+            CROUCH_SPEED_FACTOR
+        } else if player.is_running() {
+            player.run_multiplier
+        } else {
+            1.0
+        };
+        let target = if keyboard_input.pressed(KeyCode::Left) {
+            -player.speed * running_coeff
         } else if keyboard_input.pressed(KeyCode::Right) {
-            velocity.linvel.x = player.speed * running_coeff;
-        }
+            player.speed * running_coeff
+        } else {
+            0.0
+        };
+
+        // Steer relative to the belt the player stands on, if any.
+        let current = velocity.linvel.x - carried.0;
+        let rate = if target != 0.0 && current != 0.0 && target.signum() != current.signum() {
+            player.turn_acceleration
+        } else if target.abs() > current.abs() {
+            if grounded.0 {
+                player.ground_acceleration
+            } else {
+                player.air_acceleration
+            }
+        } else if grounded.0 {
+            player.ground_deceleration
+        } else {
+            player.air_deceleration
+        };
+        velocity.linvel.x = approach(current, target, rate * time.delta_seconds()) + carried.0;
+    }
+}
+
+/// Moves `current` towards `target` by at most `max_step`, without overshooting.
+fn approach(current: f32, target: f32, max_step: f32) -> f32 {
+    let difference = target - current;
+    if difference.abs() <= max_step {
+        target
+    } else {
+        current + max_step.copysign(difference)
     }
 }
 
 fn apply_movement_animation(
     mut commands: Commands,
-    query: Query<(Entity, &Player, &Velocity), Without<Animation>>,
+    query: Query<(Entity, &Player, &Grounded, &Crouch, &Velocity), Without<Animation>>,
 ) {
     if query.is_empty() {
         return;
     }
 
-    let (entity, player, velocity) = query.single();
-    if velocity.linvel.x != 0.0 && velocity.linvel.y == 0.0 {
+    let (entity, player, grounded, crouch, velocity) = query.single();
+    if velocity.linvel.x != 0.0 && grounded.0 && !crouch.is_crouching {
         let delay = if player.is_running() {
             RUN_CYCLE_DELAY
         } else {
@@ -213,20 +732,29 @@ fn apply_movement_animation(
 
 fn apply_idle_sprite(
     mut commands: Commands,
-    mut query: Query<(Entity, &Velocity, &mut TextureAtlasSprite), With<Player>>,
+    mut query: Query<
+        (
+            Entity,
+            &Grounded,
+            &Crouch,
+            &Velocity,
+            &mut TextureAtlasSprite,
+        ),
+        With<Player>,
+    >,
 ) {
     if query.is_empty() {
         return;
     }
-    let (player, velocity, mut sprite) = query.single_mut();
-    if velocity.linvel.x == 0.0 {
+    let (player, grounded, crouch, velocity, mut sprite) = query.single_mut();
+    if velocity.linvel.x == 0.0 && grounded.0 && !crouch.is_crouching {
         commands.entity(player).remove::<Animation>();
         sprite.index = SPRITE_IDX_GREEN_STAND;
     }
 }
 
-#[derive(Component)]
-enum Direction {
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
     Left,
     Right,
 }
@@ -246,14 +774,30 @@ fn update_direction(mut commands: Commands, query: Query<(Entity, &Velocity), Wi
 }
 
 fn update_sprite_direction(mut query: Query<(&mut TextureAtlasSprite, &Direction)>) {
-    if query.is_empty() {
-        return;
+    for (mut sprite, direction) in query.iter_mut() {
+        match direction {
+            Direction::Right => sprite.flip_x = false,
+            Direction::Left => sprite.flip_x = true,
+        }
     }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn running_follows_game_time() {
+        let mut player = Player::new(&PlayerConfig::default());
+        assert!(!player.is_running());
 
-    let (mut sprite, direction) = query.single_mut();
+        player.update_running(Duration::from_millis(16), true);
+        assert!(player.is_running());
 
-    match direction {
-        Direction::Right => sprite.flip_x = false,
-        Direction::Left => sprite.flip_x = true,
+        // However long a frame takes in real time, only the time passed in counts.
+        player.update_running(Duration::from_millis(60), false);
+        assert!(player.is_running());
+        player.update_running(Duration::from_millis(60), false);
+        assert!(!player.is_running());
     }
 }
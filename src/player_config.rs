@@ -0,0 +1,169 @@
+use std::time::Duration;
+
+use bevy::{
+    asset::{AssetLoader, BoxedFuture, LoadContext, LoadedAsset},
+    prelude::{
+        AddAsset, AssetEvent, AssetServer, Assets, Commands, EventReader, Handle, Plugin, Query,
+        Res, Resource, Startup, Update,
+    },
+    reflect::{TypePath, TypeUuid},
+};
+use bevy_rapier2d::prelude::Damping;
+use serde::Deserialize;
+
+use crate::player::{Jumper, Player};
+
+/// Loaded from the assets folder and reloaded whenever the file changes.
+const CONFIG_PATH: &str = "default.player.yml";
+
+pub struct PlayerConfigPlugin;
+
+impl Plugin for PlayerConfigPlugin {
+    fn build(&self, app: &mut bevy::prelude::App) {
+        app.add_asset::<PlayerConfig>()
+            .init_asset_loader::<PlayerConfigLoader>()
+            .add_systems(Startup, load_player_config)
+            .add_systems(Update, apply_player_config);
+    }
+}
+
+/// How the player moves and jumps, tunable without recompiling. Anything left out of the file
+/// keeps its default.
+#[derive(Debug, Clone, Deserialize, TypeUuid, TypePath)]
+#[uuid = "46347865-f229-4d2d-af9c-98b88f5af7cf"]
+#[serde(default)]
+pub struct PlayerConfig {
+    /// Walking speed, in units per second.
+    pub speed: f32,
+    /// Multiplies `speed` while the run key is held.
+    pub run_multiplier: f32,
+    pub ground_acceleration: f32,
+    pub air_acceleration: f32,
+    pub ground_deceleration: f32,
+    pub air_deceleration: f32,
+    pub turn_acceleration: f32,
+    /// Out of water; water sets its own.
+    pub linear_damping: f32,
+    pub jump_impulse: f32,
+    /// Letting go of jump early caps the upward speed at this fraction of `jump_impulse`.
+    pub jump_release_factor: f32,
+    pub swim_impulse: f32,
+    pub air_jumps: u8,
+    /// Gravity multiplier while falling.
+    pub fall_gravity_multiplier: f32,
+    /// Below this vertical speed at the top of a jump, gravity is multiplied by
+    /// `apex_gravity_multiplier` instead.
+    pub apex_hang_speed: f32,
+    pub apex_gravity_multiplier: f32,
+    pub terminal_fall_speed: f32,
+    /// In seconds.
+    pub coyote_time: f32,
+    pub jump_buffer_time: f32,
+}
+
+impl Default for PlayerConfig {
+    fn default() -> PlayerConfig {
+        PlayerConfig {
+            speed: 5.5,
+            run_multiplier: 3.0,
+            ground_acceleration: 60.0,
+            air_acceleration: 30.0,
+            ground_deceleration: 50.0,
+            air_deceleration: 10.0,
+            turn_acceleration: 90.0,
+            linear_damping: 0.75,
+            jump_impulse: 50.0,
+            jump_release_factor: 0.4,
+            swim_impulse: 12.0,
+            air_jumps: 1,
+            fall_gravity_multiplier: 2.0,
+            apex_hang_speed: 2.0,
+            apex_gravity_multiplier: 0.5,
+            terminal_fall_speed: 30.0,
+            coyote_time: 0.1,
+            jump_buffer_time: 0.1,
+        }
+    }
+}
+
+impl PlayerConfig {
+    /// Copies the tunables onto the player, leaving its current state alone.
+    pub fn apply(&self, player: &mut Player, jumper: &mut Jumper) {
+        player.speed = self.speed;
+        player.run_multiplier = self.run_multiplier;
+        player.ground_acceleration = self.ground_acceleration;
+        player.air_acceleration = self.air_acceleration;
+        player.ground_deceleration = self.ground_deceleration;
+        player.air_deceleration = self.air_deceleration;
+        player.turn_acceleration = self.turn_acceleration;
+        player.linear_damping = self.linear_damping;
+
+        jumper.jump_impulse = self.jump_impulse;
+        jumper.jump_release_factor = self.jump_release_factor;
+        jumper.swim_impulse = self.swim_impulse;
+        jumper.air_jumps = self.air_jumps;
+        jumper.fall_gravity_multiplier = self.fall_gravity_multiplier;
+        jumper.apex_hang_speed = self.apex_hang_speed;
+        jumper.apex_gravity_multiplier = self.apex_gravity_multiplier;
+        jumper.terminal_fall_speed = self.terminal_fall_speed;
+        jumper
+            .coyote_time
+            .set_duration(Duration::from_secs_f32(self.coyote_time));
+        jumper
+            .jump_buffer
+            .set_duration(Duration::from_secs_f32(self.jump_buffer_time));
+    }
+}
+
+#[derive(Default)]
+struct PlayerConfigLoader;
+
+impl AssetLoader for PlayerConfigLoader {
+    fn load<'a>(
+        &'a self,
+        bytes: &'a [u8],
+        load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<(), bevy::asset::Error>> {
+        Box::pin(async move {
+            let config: PlayerConfig = serde_yaml::from_slice(bytes)?;
+            load_context.set_default_asset(LoadedAsset::new(config));
+            Ok(())
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["player.yml"]
+    }
+}
+
+/// Keeps the config loaded, so edits to the file are picked up.
+#[derive(Resource)]
+struct PlayerConfigHandle(Handle<PlayerConfig>);
+
+fn load_player_config(mut commands: Commands, server: Res<AssetServer>) {
+    commands.insert_resource(PlayerConfigHandle(server.load(CONFIG_PATH)));
+}
+
+fn apply_player_config(
+    mut events: EventReader<AssetEvent<PlayerConfig>>,
+    handle: Res<PlayerConfigHandle>,
+    configs: Res<Assets<PlayerConfig>>,
+    mut players: Query<(&mut Player, &mut Jumper, &mut Damping)>,
+) {
+    let is_changed = events.iter().any(|event| match event {
+        AssetEvent::Created { handle: changed } | AssetEvent::Modified { handle: changed } => {
+            *changed == handle.0
+        }
+        AssetEvent::Removed { .. } => false,
+    });
+    let Some(config) = configs.get(&handle.0).filter(|_| is_changed) else {
+        return;
+    };
+
+    for (mut player, mut jumper, mut damping) in players.iter_mut() {
+        config.apply(&mut player, &mut jumper);
+        if !jumper.is_swimming {
+            damping.linear_damping = config.linear_damping;
+        }
+    }
+}
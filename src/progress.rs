@@ -0,0 +1,123 @@
+use std::{
+    collections::{HashMap, HashSet},
+    path::PathBuf,
+    time::Duration,
+};
+
+use bevy::{
+    prelude::{
+        in_state, Event, EventReader, IntoSystemConfigs, Plugin, Res, ResMut, Resource, Startup,
+        Update,
+    },
+    time::{Stopwatch, Time},
+};
+
+use crate::{
+    level::{self, LevelSummary},
+    save, trigger, GameState,
+};
+
+pub struct ProgressPlugin;
+
+impl Plugin for ProgressPlugin {
+    fn build(&self, app: &mut bevy::prelude::App) {
+        app.init_resource::<Progress>()
+            .init_resource::<LevelTimer>()
+            .insert_resource(SaveFile(save::save_path()))
+            .add_event::<LevelCompleted>()
+            .add_systems(Startup, load_progress)
+            .add_systems(
+                Update,
+                tick_level_timer.run_if(in_state(GameState::Playing)),
+            )
+            // The level is left right after it is completed, so this can't wait for `Playing`,
+            // and has to see the event in the same frame, before the next level's timer starts.
+            .add_systems(
+                Update,
+                record_completion
+                    .after(level::exit_level)
+                    .after(trigger::dispatch_actions),
+            );
+    }
+}
+
+/// The player finished the level that is currently loaded.
+#[derive(Debug, Event)]
+pub struct LevelCompleted;
+
+/// Levels the player has finished, by level id, how fast, and how many coins they collected.
+#[derive(Resource, Default, Debug)]
+pub struct Progress {
+    pub completed: HashSet<String>,
+    pub best_times: HashMap<String, Duration>,
+    pub coins: HashMap<String, u32>,
+}
+
+impl Progress {
+    /// The first level is always open; every other one opens once the level before it is done.
+    pub fn is_unlocked(&self, levels: &[LevelSummary], idx: usize) -> bool {
+        idx == 0
+            || self.completed.contains(&levels[idx].id)
+            || self.completed.contains(&levels[idx - 1].id)
+    }
+
+    /// Marks `level_id` as completed, keeping `time` if it beats the previous best.
+    pub fn complete(&mut self, level_id: &str, time: Duration) {
+        self.completed.insert(level_id.to_string());
+        let best = self.best_times.entry(level_id.to_string()).or_insert(time);
+        if time < *best {
+            *best = time;
+        }
+    }
+}
+
+/// Where progress is saved. `None` if there is nowhere to put it.
+#[derive(Resource, Debug)]
+pub struct SaveFile(pub Option<PathBuf>);
+
+/// Time spent playing the current level. Reset whenever a level is loaded.
+#[derive(Resource, Default, Debug)]
+pub struct LevelTimer {
+    pub level_id: String,
+    pub elapsed: Stopwatch,
+}
+
+impl LevelTimer {
+    pub fn new(level_id: String) -> LevelTimer {
+        LevelTimer {
+            level_id,
+            elapsed: Stopwatch::new(),
+        }
+    }
+}
+
+fn load_progress(save_file: Res<SaveFile>, mut progress: ResMut<Progress>) {
+    let Some(path) = &save_file.0 else {
+        return;
+    };
+    match save::load(path) {
+        Ok(saved) => *progress = saved,
+        Err(err) => eprintln!("starting without saved progress: {err:#}"),
+    }
+}
+
+fn tick_level_timer(time: Res<Time>, mut timer: ResMut<LevelTimer>) {
+    timer.elapsed.tick(time.delta());
+}
+
+fn record_completion(
+    mut completions: EventReader<LevelCompleted>,
+    timer: Res<LevelTimer>,
+    save_file: Res<SaveFile>,
+    mut progress: ResMut<Progress>,
+) {
+    if completions.iter().last().is_none() {
+        return;
+    }
+    progress.complete(&timer.level_id, timer.elapsed.elapsed());
+    if let Some(path) = &save_file.0 {
+        if let Err(err) = save::write(path, &progress) {
+            eprintln!("couldn't save progress: {err:#}");
+        }
+    }
+}
@@ -0,0 +1,142 @@
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use anyhow::Context;
+
+use crate::progress::Progress;
+
+/// Bumped whenever the meaning of an existing field changes. New fields get a serde default
+/// instead, so older saves keep loading.
+const SAVE_VERSION: u32 = 1;
+const SAVE_FILE_NAME: &str = "save.yml";
+
+#[derive(serde::Serialize, serde::Deserialize, Debug, PartialEq)]
+struct SaveData {
+    version: u32,
+    #[serde(default)]
+    completed: Vec<String>,
+    /// Seconds, by level id.
+    #[serde(default)]
+    best_times: HashMap<String, f64>,
+    /// Coins collected, by level id.
+    #[serde(default)]
+    coins: HashMap<String, u32>,
+}
+
+/// Where progress is kept: the path after `--save` on the command line, or `save.yml` in the
+/// platform's data directory.
+pub fn save_path() -> Option<PathBuf> {
+    let mut args = std::env::args().skip_while(|arg| arg != "--save");
+    if let Some(path) = args.nth(1) {
+        return Some(PathBuf::from(path));
+    }
+    dirs::data_dir().map(|dir| dir.join("platformer").join(SAVE_FILE_NAME))
+}
+
+/// Reads the save at `path`. A missing file is a fresh start, not an error.
+pub fn load(path: &Path) -> anyhow::Result<Progress> {
+    let text = match std::fs::read_to_string(path) {
+        Ok(text) => text,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(Progress::default()),
+        Err(err) => return Err(err).with_context(|| format!("couldn't read {}", path.display())),
+    };
+    let data: SaveData = serde_yaml::from_str(&text)
+        .with_context(|| format!("invalid save file {}", path.display()))?;
+    if data.version > SAVE_VERSION {
+        anyhow::bail!(
+            "{} was written by a newer version of the game (save version {})",
+            path.display(),
+            data.version
+        );
+    }
+    let best_times = data
+        .best_times
+        .into_iter()
+        .map(|(id, seconds)| {
+            let time = Duration::try_from_secs_f64(seconds)
+                .with_context(|| format!("invalid best time for `{id}` in {}", path.display()))?;
+            Ok((id, time))
+        })
+        .collect::<anyhow::Result<_>>()?;
+    Ok(Progress {
+        completed: data.completed.into_iter().collect(),
+        best_times,
+        coins: data.coins,
+    })
+}
+
+/// Writes `progress` to `path`, replacing the previous save only once the new one is complete.
+pub fn write(path: &Path, progress: &Progress) -> anyhow::Result<()> {
+    let mut completed: Vec<String> = progress.completed.iter().cloned().collect();
+    completed.sort();
+    let data = SaveData {
+        version: SAVE_VERSION,
+        completed,
+        best_times: progress
+            .best_times
+            .iter()
+            .map(|(id, time)| (id.clone(), time.as_secs_f64()))
+            .collect(),
+        coins: progress.coins.clone(),
+    };
+
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)
+            .with_context(|| format!("couldn't create {}", dir.display()))?;
+    }
+    let temp_path = path.with_extension("yml.tmp");
+    std::fs::write(&temp_path, serde_yaml::to_string(&data)?)
+        .with_context(|| format!("couldn't write {}", temp_path.display()))?;
+    std::fs::rename(&temp_path, path)
+        .with_context(|| format!("couldn't replace {}", path.display()))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_dir::TestDir;
+
+    #[test]
+    fn save_round_trip() {
+        let dir = TestDir::new("save-round-trip");
+        let path = &dir.join(SAVE_FILE_NAME);
+        let mut progress = Progress::default();
+        progress.complete("first", Duration::from_millis(12_500));
+        progress.coins.insert("first".to_string(), 7);
+        write(path, &progress).unwrap();
+
+        let loaded = load(path).unwrap();
+        assert!(loaded.completed.contains("first"));
+        assert_eq!(loaded.best_times["first"], Duration::from_millis(12_500));
+        assert_eq!(loaded.coins["first"], 7);
+        assert!(!path.with_extension("yml.tmp").exists());
+    }
+
+    #[test]
+    fn load_save_rejects_bad_times() {
+        let dir = TestDir::new("save-bad-times");
+        let path = &dir.join(SAVE_FILE_NAME);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, "version: 1\nbest_times: {first: -1.0}\n").unwrap();
+
+        let error = load(path).unwrap_err();
+        assert!(error.to_string().contains("invalid best time for `first`"));
+    }
+
+    #[test]
+    fn load_save_with_missing_and_unknown_fields() {
+        let dir = TestDir::new("save-fields");
+        let path = &dir.join(SAVE_FILE_NAME);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, "version: 1\ncompleted: [first]\nstars: {first: 3}\n").unwrap();
+
+        let loaded = load(path).unwrap();
+        assert!(loaded.completed.contains("first"));
+        assert!(loaded.best_times.is_empty());
+        assert!(loaded.coins.is_empty());
+    }
+}
@@ -0,0 +1,131 @@
+use bevy::{
+    prelude::{
+        in_state, BuildChildren, Children, Color, Commands, Component, Entity, Handle,
+        IntoSystemConfigs, Plugin, Query, SpatialBundle, Transform, Update, Vec2, Vec3, Visibility,
+        With,
+    },
+    sprite::{Sprite, SpriteBundle, SpriteSheetBundle, TextureAtlas, TextureAtlasSprite},
+    text::{Text, Text2dBounds, Text2dBundle, TextAlignment, TextStyle},
+};
+
+use crate::{level::SignDefinition, player::Player, GameState};
+
+const SIGN_SPRITE_INDEX: usize = 34;
+
+/// Distance from the sign at which its hint appears.
+const HINT_RANGE: f32 = 2.5;
+
+const HINT_FONT_SIZE: f32 = 40.0;
+/// Text is laid out in pixels, this brings it down to world units.
+const HINT_TEXT_SCALE: f32 = 0.02;
+const HINT_BUBBLE_WIDTH: f32 = 8.0;
+/// Rough number of characters that fit on one line of the bubble.
+const HINT_CHARS_PER_LINE: usize = 20;
+
+pub struct SignPlugin;
+
+impl Plugin for SignPlugin {
+    fn build(&self, app: &mut bevy::prelude::App) {
+        app.add_systems(Update, (show_hints,).run_if(in_state(GameState::Playing)));
+    }
+}
+
+#[derive(Component, Debug)]
+pub struct Sign;
+
+#[derive(Component, Debug)]
+pub struct Hint;
+
+pub fn setup(
+    commands: &mut Commands,
+    tile_atlas: &Handle<TextureAtlas>,
+    level: Entity,
+    sign: &SignDefinition,
+) {
+    let mut sprite = TextureAtlasSprite::new(SIGN_SPRITE_INDEX);
+    sprite.custom_size = Some(Vec2::new(1.0, 1.0));
+
+    let lines = sign
+        .text
+        .lines()
+        .map(|line| line.len().max(1).div_ceil(HINT_CHARS_PER_LINE))
+        .sum::<usize>();
+    let bubble_height = lines as f32 * HINT_FONT_SIZE * HINT_TEXT_SCALE + 0.5;
+
+    commands.entity(level).with_children(|children| {
+        children
+            .spawn(SpriteSheetBundle {
+                sprite,
+                texture_atlas: tile_atlas.clone(),
+                transform: Transform::from_translation(sign.location.extend(0.5)),
+                ..SpriteSheetBundle::default()
+            })
+            .insert(Sign)
+            .with_children(|children| {
+                children
+                    .spawn(SpatialBundle {
+                        transform: Transform::from_translation(Vec3::new(
+                            0.0,
+                            1.0 + bubble_height / 2.0,
+                            2.0,
+                        )),
+                        visibility: Visibility::Hidden,
+                        ..SpatialBundle::default()
+                    })
+                    .insert(Hint)
+                    .with_children(|children| {
+                        children.spawn(SpriteBundle {
+                            sprite: Sprite {
+                                color: Color::rgba(0.0, 0.0, 0.0, 0.7),
+                                custom_size: Some(Vec2::new(HINT_BUBBLE_WIDTH, bubble_height)),
+                                ..Sprite::default()
+                            },
+                            ..SpriteBundle::default()
+                        });
+                        children.spawn(Text2dBundle {
+                            text: Text::from_section(
+                                sign.text.clone(),
+                                TextStyle {
+                                    font_size: HINT_FONT_SIZE,
+                                    color: Color::WHITE,
+                                    ..TextStyle::default()
+                                },
+                            )
+                            .with_alignment(TextAlignment::Center),
+                            text_2d_bounds: Text2dBounds {
+                                size: Vec2::new(
+                                    (HINT_BUBBLE_WIDTH - 0.5) / HINT_TEXT_SCALE,
+                                    f32::INFINITY,
+                                ),
+                            },
+                            transform: Transform::from_translation(Vec3::new(0.0, 0.0, 0.1))
+                                .with_scale(Vec3::splat(HINT_TEXT_SCALE)),
+                            ..Text2dBundle::default()
+                        });
+                    });
+            });
+    });
+}
+
+fn show_hints(
+    signs: Query<(&Transform, &Children), With<Sign>>,
+    mut hints: Query<&mut Visibility, With<Hint>>,
+    players: Query<&Transform, With<Player>>,
+) {
+    if players.is_empty() {
+        return;
+    }
+    let player_position = players.single().translation.truncate();
+
+    for (sign_transform, children) in signs.iter() {
+        let is_near = player_position.distance(sign_transform.translation.truncate()) < HINT_RANGE;
+        let mut hint_iter = hints.iter_many_mut(children);
+        while let Some(mut visibility) = hint_iter.fetch_next() {
+            *visibility = if is_near {
+                Visibility::Inherited
+            } else {
+                Visibility::Hidden
+            };
+        }
+    }
+}
@@ -0,0 +1,23 @@
+use std::path::{Path, PathBuf};
+
+/// A fresh directory under the system temp dir for a test's files, removed again when dropped.
+pub struct TestDir(PathBuf);
+
+impl TestDir {
+    /// `name` has to be unique among the tests, which run in parallel.
+    pub fn new(name: &str) -> TestDir {
+        let dir = std::env::temp_dir().join(format!("platformer-{}-{name}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        TestDir(dir)
+    }
+
+    pub fn join(&self, path: impl AsRef<Path>) -> PathBuf {
+        self.0.join(path)
+    }
+}
+
+impl Drop for TestDir {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.0);
+    }
+}
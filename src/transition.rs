@@ -0,0 +1,107 @@
+use bevy::{
+    prelude::{
+        in_state, Color, Commands, Component, IntoSystemConfigs, NextState, NodeBundle, OnEnter,
+        Plugin, Query, Res, ResMut, Resource, Startup, Update, With,
+    },
+    time::{Time, Timer, TimerMode},
+    ui::{BackgroundColor, PositionType, Style, Val, ZIndex},
+};
+
+use crate::GameState;
+
+const FADE_SECONDS: f32 = 0.4;
+
+pub struct TransitionPlugin;
+
+impl Plugin for TransitionPlugin {
+    fn build(&self, app: &mut bevy::prelude::App) {
+        app.init_resource::<Fade>()
+            .add_systems(Startup, spawn_fade_overlay)
+            .add_systems(
+                Update,
+                update_fade.run_if(in_state(GameState::LevelTransition)),
+            )
+            .add_systems(OnEnter(GameState::WorldComplete), clear_fade);
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FadeDirection {
+    /// To black, after which the next level is loaded.
+    Out,
+    /// From black, after which play resumes.
+    In,
+}
+
+/// The fade currently shown by the `LevelTransition` state.
+#[derive(Resource, Debug)]
+pub struct Fade {
+    direction: FadeDirection,
+    timer: Timer,
+}
+
+impl Default for Fade {
+    fn default() -> Fade {
+        Fade {
+            direction: FadeDirection::In,
+            timer: Timer::from_seconds(FADE_SECONDS, TimerMode::Once),
+        }
+    }
+}
+
+impl Fade {
+    pub fn start(&mut self, direction: FadeDirection) {
+        self.direction = direction;
+        self.timer.reset();
+    }
+}
+
+/// Full-screen black node drawn over everything else, menus included.
+#[derive(Component)]
+struct FadeOverlay;
+
+fn spawn_fade_overlay(mut commands: Commands) {
+    commands
+        .spawn(NodeBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                ..Style::default()
+            },
+            background_color: BackgroundColor(Color::rgba(0.0, 0.0, 0.0, 0.0)),
+            z_index: ZIndex::Global(i32::MAX),
+            ..NodeBundle::default()
+        })
+        .insert(FadeOverlay);
+}
+
+fn update_fade(
+    time: Res<Time>,
+    mut fade: ResMut<Fade>,
+    mut overlays: Query<&mut BackgroundColor, With<FadeOverlay>>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    fade.timer.tick(time.delta());
+    let darkness = match fade.direction {
+        FadeDirection::Out => fade.timer.percent(),
+        FadeDirection::In => fade.timer.percent_left(),
+    };
+    for mut color in overlays.iter_mut() {
+        color.0.set_a(darkness);
+    }
+
+    if fade.timer.finished() {
+        next_state.set(match fade.direction {
+            FadeDirection::Out => GameState::LoadingLevel,
+            FadeDirection::In => GameState::Playing,
+        });
+    }
+}
+
+/// There is no level to fade into at the end of the world, so the win screen shows straight away.
+fn clear_fade(mut overlays: Query<&mut BackgroundColor, With<FadeOverlay>>) {
+    for mut color in overlays.iter_mut() {
+        color.0.set_a(0.0);
+    }
+}
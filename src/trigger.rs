@@ -0,0 +1,239 @@
+use std::time::Duration;
+
+use bevy::{
+    audio::{AudioBundle, PlaybackSettings},
+    math::Rect,
+    prelude::{
+        in_state, AssetServer, BuildChildren, Camera2d, Color, Commands, Component,
+        DespawnRecursiveExt, Entity, Event, EventReader, EventWriter, IntoSystemConfigs,
+        NodeBundle, OnEnter, Plugin, Query, Res, TextBundle, Transform, Update, Vec2, Visibility,
+        With, Without,
+    },
+    text::{TextAlignment, TextStyle},
+    time::{Time, Timer, TimerMode},
+    ui::{AlignItems, JustifyContent, PositionType, Style, UiRect, Val},
+};
+use bevy_rapier2d::prelude::{Collider, ColliderDisabled};
+
+use crate::{
+    elevator::{Elevator, State},
+    level::{Action, ChangeLevel, FeatureId, Level, TriggerDefinition},
+    player::Player,
+    progress::LevelCompleted,
+    GameState, CAMERA_SCALE,
+};
+
+const DEFAULT_TEXT_SECONDS: f32 = 3.0;
+
+pub struct TriggerPlugin;
+
+impl Plugin for TriggerPlugin {
+    fn build(&self, app: &mut bevy::prelude::App) {
+        app.add_event::<TriggerAction>()
+            .add_systems(
+                Update,
+                (detect_triggers, dispatch_actions, expire_shown_text)
+                    .run_if(in_state(GameState::Playing)),
+            )
+            .add_systems(OnEnter(GameState::LoadingLevel), clear_trigger_effects);
+    }
+}
+
+#[derive(Component, Debug)]
+pub struct Trigger {
+    pub bounds: Rect,
+    pub on_enter: Vec<Action>,
+    pub on_exit: Vec<Action>,
+    pub on_stay: Vec<Action>,
+    pub is_occupied: bool,
+}
+
+/// An action fired by a trigger, waiting to be carried out.
+#[derive(Debug, Event)]
+pub struct TriggerAction(pub Action);
+
+/// Text put on screen by [`Action::ShowText`], removed once the timer runs out.
+#[derive(Component)]
+struct ShownText {
+    timer: Timer,
+}
+
+pub fn setup(commands: &mut Commands, level: Entity, trigger: &TriggerDefinition) {
+    let bottom_left = Vec2::new(trigger.loc.x, trigger.loc.y - 0.5);
+    commands.entity(level).with_children(|children| {
+        children.spawn(Trigger {
+            bounds: Rect::from_corners(bottom_left, bottom_left + trigger.size),
+            on_enter: trigger.on_enter.clone(),
+            on_exit: trigger.on_exit.clone(),
+            on_stay: trigger.on_stay.clone(),
+            is_occupied: false,
+        });
+    });
+}
+
+fn detect_triggers(
+    mut triggers: Query<&mut Trigger>,
+    players: Query<&Transform, With<Player>>,
+    mut actions: EventWriter<TriggerAction>,
+) {
+    if players.is_empty() {
+        return;
+    }
+    let player_position = players.single().translation.truncate();
+
+    for mut trigger in triggers.iter_mut() {
+        let is_inside = trigger.bounds.contains(player_position);
+        let fired = match (trigger.is_occupied, is_inside) {
+            (false, true) => &trigger.on_enter,
+            (true, true) => &trigger.on_stay,
+            (true, false) => &trigger.on_exit,
+            (false, false) => continue,
+        };
+        actions.send_batch(fired.iter().cloned().map(TriggerAction));
+        trigger.is_occupied = is_inside;
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn dispatch_actions(
+    mut commands: Commands,
+    mut actions: EventReader<TriggerAction>,
+    mut change_level: EventWriter<ChangeLevel>,
+    mut level_completed: EventWriter<LevelCompleted>,
+    server: Res<AssetServer>,
+    mut elevators: Query<(&FeatureId, &mut State), With<Elevator>>,
+    mut blocks: Query<
+        (
+            Entity,
+            &FeatureId,
+            &mut Visibility,
+            Option<&Collider>,
+            Option<&ColliderDisabled>,
+        ),
+        Without<Elevator>,
+    >,
+    mut cameras: Query<&mut Transform, With<Camera2d>>,
+    levels: Query<&Level>,
+) {
+    for TriggerAction(action) in actions.iter() {
+        match action {
+            Action::ShowText { text, seconds } => {
+                let seconds = seconds.unwrap_or(DEFAULT_TEXT_SECONDS);
+                spawn_shown_text(&mut commands, text, seconds);
+            }
+            Action::StartElevator { id } => {
+                for (feature_id, mut state) in elevators.iter_mut() {
+                    if &feature_id.0 == id && matches!(*state, State::Stopped) {
+                        *state = State::MovingForward;
+                    }
+                }
+            }
+            Action::StopElevator { id } => {
+                for (feature_id, mut state) in elevators.iter_mut() {
+                    if &feature_id.0 == id {
+                        *state = State::Stopped;
+                    }
+                }
+            }
+            Action::ToggleBlock { id } => {
+                for (entity, feature_id, mut visibility, collider, disabled) in blocks.iter_mut() {
+                    if &feature_id.0 != id {
+                        continue;
+                    }
+                    *visibility = match *visibility {
+                        Visibility::Hidden => Visibility::Inherited,
+                        _ => Visibility::Hidden,
+                    };
+                    match (collider, disabled) {
+                        (Some(_), Some(_)) => {
+                            commands.entity(entity).remove::<ColliderDisabled>();
+                        }
+                        (Some(_), None) => {
+                            commands.entity(entity).insert(ColliderDisabled);
+                        }
+                        (None, _) => {}
+                    }
+                }
+            }
+            Action::CameraZoom { scale } => {
+                for mut transform in cameras.iter_mut() {
+                    transform.scale.x = *scale;
+                    transform.scale.y = *scale;
+                }
+            }
+            Action::PlaySound { path } => {
+                commands.spawn(AudioBundle {
+                    source: server.load(path.as_str()),
+                    settings: PlaybackSettings::DESPAWN,
+                });
+            }
+            Action::EndLevel => {
+                if let Ok(level) = levels.get_single() {
+                    change_level.send(ChangeLevel {
+                        destination: level.idx + 1,
+                    });
+                    level_completed.send(LevelCompleted);
+                }
+            }
+        }
+    }
+}
+
+fn spawn_shown_text(commands: &mut Commands, text: &str, seconds: f32) {
+    commands
+        .spawn(NodeBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                width: Val::Percent(100.0),
+                top: Val::Px(0.0),
+                padding: UiRect::all(Val::Px(16.0)),
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                ..Style::default()
+            },
+            ..NodeBundle::default()
+        })
+        .insert(ShownText {
+            timer: Timer::new(Duration::from_secs_f32(seconds), TimerMode::Once),
+        })
+        .with_children(|children| {
+            children.spawn(
+                TextBundle::from_section(
+                    text,
+                    TextStyle {
+                        font_size: 28.0,
+                        color: Color::WHITE,
+                        ..TextStyle::default()
+                    },
+                )
+                .with_text_alignment(TextAlignment::Center),
+            );
+        });
+}
+
+fn expire_shown_text(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut shown_texts: Query<(Entity, &mut ShownText)>,
+) {
+    for (entity, mut shown_text) in shown_texts.iter_mut() {
+        if shown_text.timer.tick(time.delta()).finished() {
+            commands.entity(entity).despawn_recursive();
+        }
+    }
+}
+
+/// Undoes what triggers changed outside the level, so the next level starts from a clean slate.
+fn clear_trigger_effects(
+    mut commands: Commands,
+    shown_texts: Query<Entity, With<ShownText>>,
+    mut cameras: Query<&mut Transform, With<Camera2d>>,
+) {
+    for entity in shown_texts.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+    for mut transform in cameras.iter_mut() {
+        transform.scale.x = CAMERA_SCALE;
+        transform.scale.y = CAMERA_SCALE;
+    }
+}
@@ -0,0 +1,102 @@
+use bevy::{
+    math::Rect,
+    prelude::{
+        in_state, BuildChildren, Color, Commands, Component, Entity, Handle, IntoSystemConfigs,
+        Plugin, Query, Transform, Update, Vec2, Vec3,
+    },
+    sprite::{SpriteSheetBundle, TextureAtlas, TextureAtlasSprite},
+};
+use bevy_rapier2d::prelude::Damping;
+
+use crate::{
+    level::WaterDefinition,
+    player::{Jumper, Player},
+    GameState,
+};
+
+const WATER_TOP_SPRITE_INDEX: usize = 17;
+const WATER_SPRITE_INDEX: usize = 22;
+const WATER_ALPHA: f32 = 0.6;
+
+pub const WATER_GRAVITY_SCALE: f32 = 0.3;
+const WATER_LINEAR_DAMPING: f32 = 4.0;
+
+pub struct WaterPlugin;
+
+impl Plugin for WaterPlugin {
+    fn build(&self, app: &mut bevy::prelude::App) {
+        app.add_systems(
+            Update,
+            (update_swimming,).run_if(in_state(GameState::Playing)),
+        );
+    }
+}
+
+#[derive(Component, Debug)]
+pub struct Water {
+    pub bounds: Rect,
+}
+
+pub fn setup(
+    commands: &mut Commands,
+    tile_atlas: &Handle<TextureAtlas>,
+    level: Entity,
+    water: &WaterDefinition,
+) {
+    let columns = water.size.x.ceil() as usize;
+    let rows = water.size.y.ceil() as usize;
+
+    // Tiles are centered on their location, so the zone starts half a tile below `loc`.
+    let bottom_left = Vec2::new(water.loc.x, water.loc.y - 0.5);
+    let bounds = Rect::from_corners(bottom_left, bottom_left + water.size);
+
+    commands.entity(level).with_children(|children| {
+        children.spawn(Water { bounds });
+        for row in 0..rows {
+            let index = if row + 1 == rows {
+                WATER_TOP_SPRITE_INDEX
+            } else {
+                WATER_SPRITE_INDEX
+            };
+            let mut sprite = TextureAtlasSprite::new(index);
+            sprite.custom_size = Some(Vec2::new(1.0, 1.0));
+            sprite.color = Color::rgba(1.0, 1.0, 1.0, WATER_ALPHA);
+
+            for column in 0..columns {
+                // In front of the player, so they look submerged.
+                let translation = Vec3::new(
+                    water.loc.x + column as f32 + 0.5,
+                    water.loc.y + row as f32,
+                    2.0,
+                );
+                children.spawn(SpriteSheetBundle {
+                    sprite: sprite.clone(),
+                    texture_atlas: tile_atlas.clone(),
+                    transform: Transform::from_translation(translation),
+                    ..SpriteSheetBundle::default()
+                });
+            }
+        }
+    });
+}
+
+fn update_swimming(
+    waters: Query<&Water>,
+    mut players: Query<(&Player, &Transform, &mut Jumper, &mut Damping)>,
+) {
+    for (player, transform, mut jumper, mut damping) in players.iter_mut() {
+        let position = transform.translation.truncate();
+        let is_swimming = waters.iter().any(|water| water.bounds.contains(position));
+        if is_swimming == jumper.is_swimming {
+            continue;
+        }
+
+        jumper.is_swimming = is_swimming;
+        // Gravity follows `is_swimming` in the player's own gravity system.
+        if is_swimming {
+            damping.linear_damping = WATER_LINEAR_DAMPING;
+        } else {
+            damping.linear_damping = player.linear_damping;
+        }
+    }
+}